@@ -0,0 +1,30 @@
+#![no_main]
+use std::ops::RangeBounds;
+
+use interval_map::Interval;
+use libfuzzer_sys::fuzz_target;
+
+fn contains(interval: &Interval<u8>, p: u8) -> bool {
+    interval.start <= p && interval.end >= p
+}
+
+fuzz_target!(|intervals: (Interval<u8>, Interval<u8>)| {
+    let (a, b) = intervals;
+    if a.is_empty() {
+        assert!((0..=255).all(|p| !contains(&a, p)));
+    }
+    for p in 0..=255 {
+        assert_eq!(contains(&a, p), RangeBounds::contains(&a, &p));
+    }
+    let _ = a.start <= b.end;
+    let _ = a.end < b.start;
+    match a.intersection(&b) {
+        Some(i) => {
+            assert!(!i.is_empty());
+            for p in 0..=255 {
+                assert_eq!(contains(&i, p), contains(&a, p) && contains(&b, p));
+            }
+        }
+        None => assert!((0..=255).all(|p| !contains(&a, p) || !contains(&b, p))),
+    }
+});
@@ -0,0 +1,176 @@
+#![no_main]
+use arbitrary::Arbitrary;
+use interval_map::{
+    bound::StartBound,
+    interval_map::{ConflictPolicy, Entry},
+    Interval, IntervalMap,
+};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Policy {
+    FirstWins,
+    LastWins,
+    Error,
+    Combine,
+}
+
+#[derive(Arbitrary, Debug)]
+enum EntryOp {
+    OrInsert(u8),
+    InsertGap(u8),
+    InsertWithin(Interval<u8>, u8),
+    SplitAt(StartBound<u8>),
+    ShrinkTo(Interval<u8>),
+    ExtendTo(Interval<u8>),
+    RemovePart(Interval<u8>),
+    Remove,
+}
+
+#[derive(Arbitrary, Debug)]
+enum CursorOp {
+    MoveNext,
+    MovePrev,
+    InsertBefore(Interval<u8>, u8),
+    InsertAfter(Interval<u8>, u8),
+    SplitCurrent(StartBound<u8>),
+    RemoveCurrent,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Insert(Interval<u8>, u8),
+    InsertReturning(Interval<u8>, u8),
+    InsertExclusive(Interval<u8>, u8),
+    InsertWith(Interval<u8>, u8),
+    Overwrite(Interval<u8>, u8),
+    OverwriteReturning(Interval<u8>, u8),
+    Remove(Interval<u8>),
+    RemoveReturning(Interval<u8>),
+    Append(IntervalMap<u8, u8>),
+    Invert(u8),
+    SplitOff(u8),
+    SplitOffAt(StartBound<u8>),
+    SplitRange(Interval<u8>),
+    Merge(IntervalMap<u8, u8>),
+    ApplyDiff(IntervalMap<u8, u8>),
+    FromIterWithPolicy(Vec<(Interval<u8>, u8)>, Policy),
+    Entry(u8, EntryOp),
+    Cursor(u8, Vec<CursorOp>),
+}
+
+fn combine(x: &u8, y: &u8) -> u8 {
+    x.wrapping_add(*y)
+}
+
+fn check<V>(map: &IntervalMap<u8, V>) {
+    assert!(map.keys().all(|i| !i.is_empty()));
+    assert!(map.inner().windows(2).all(|w| w[0].0.end < w[1].0.start));
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut map = IntervalMap::<u8, u8>::new();
+    for op in ops {
+        match op {
+            Op::Insert(i, v) => map.insert(i, v),
+            Op::InsertReturning(i, v) => {
+                for gap in map.insert_returning(i, v) {
+                    assert!(!gap.is_empty());
+                }
+            }
+            Op::InsertExclusive(i, v) => {
+                let vacant = map.is_vacant(i);
+                assert_eq!(map.try_insert_exclusive(i, v).is_ok(), vacant);
+            }
+            Op::InsertWith(i, v) => map.insert_with(i, v, |x, y| *x = x.wrapping_add(*y)),
+            Op::Overwrite(i, v) => map.overwrite(i, v),
+            Op::OverwriteReturning(i, v) => {
+                map.overwrite_returning(i, v);
+            }
+            Op::Remove(i) => map.remove(i),
+            Op::RemoveReturning(i) => {
+                map.remove_returning(i);
+            }
+            Op::Append(mut other) => {
+                map.append(&mut other);
+                assert!(other.is_empty());
+            }
+            Op::Invert(v) => map.invert(v),
+            Op::SplitOff(k) => check(&map.split_off(k)),
+            Op::SplitOffAt(at) => check(&map.split_off_at(at)),
+            Op::SplitRange(window) => {
+                let (before, within, after) = map.clone().split_range(window);
+                check(&before);
+                check(&within);
+                check(&after);
+            }
+            Op::Merge(other) => check(&map.merge(&other)),
+            Op::ApplyDiff(other) => {
+                let diff: Vec<_> = map.diff(&other).collect();
+                let mut applied = map.clone();
+                applied.apply_diff(diff);
+                check(&applied);
+                assert!(applied.diff(&other).next().is_none());
+                map = applied;
+            }
+            Op::FromIterWithPolicy(items, policy) => {
+                let policy = match policy {
+                    Policy::FirstWins => ConflictPolicy::FirstWins,
+                    Policy::LastWins => ConflictPolicy::LastWins,
+                    Policy::Error => ConflictPolicy::Error,
+                    Policy::Combine => ConflictPolicy::Combine(combine),
+                };
+                if let Ok(built) = IntervalMap::from_iter_with_policy(items, policy) {
+                    map = built;
+                }
+            }
+            Op::Entry(k, op) => match (map.entry(k), op) {
+                (entry, EntryOp::OrInsert(v)) => {
+                    entry.or_insert(v);
+                }
+                (Entry::Vacant(entry), EntryOp::InsertGap(v)) => {
+                    entry.insert_gap(v);
+                }
+                (Entry::Vacant(entry), EntryOp::InsertWithin(i, v)) => {
+                    let _ = entry.insert_within(i, v);
+                }
+                (Entry::Occupied(mut entry), EntryOp::SplitAt(at)) => {
+                    let _ = entry.split_at(at);
+                }
+                (Entry::Occupied(mut entry), EntryOp::ShrinkTo(i)) => {
+                    let _ = entry.shrink_to(i);
+                }
+                (Entry::Occupied(mut entry), EntryOp::ExtendTo(i)) => {
+                    let _ = entry.extend_to(i);
+                }
+                (Entry::Occupied(entry), EntryOp::RemovePart(i)) => entry.remove_part(i),
+                (Entry::Occupied(entry), EntryOp::Remove) => {
+                    entry.remove();
+                }
+                _ => {}
+            },
+            Op::Cursor(k, ops) => {
+                let mut cursor = map.cursor_at_mut(&k);
+                for op in ops {
+                    match op {
+                        CursorOp::MoveNext => cursor.move_next(),
+                        CursorOp::MovePrev => cursor.move_prev(),
+                        CursorOp::InsertBefore(i, v) => {
+                            let _ = cursor.insert_before(i, v);
+                        }
+                        CursorOp::InsertAfter(i, v) => {
+                            let _ = cursor.insert_after(i, v);
+                        }
+                        CursorOp::SplitCurrent(at) => {
+                            let _ = cursor.split_current(at);
+                        }
+                        CursorOp::RemoveCurrent => {
+                            cursor.remove_current();
+                        }
+                    }
+                }
+            }
+        }
+        check(&map);
+    }
+});
@@ -6,12 +6,51 @@ use {
     quote::{quote, ToTokens},
 };
 
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub struct StartBound<T>(pub Bound<T>);
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub struct EndBound<T>(pub Bound<T>);
 
+impl<T> StartBound<T> {
+    pub fn value(&self) -> Option<&T> {
+        use Bound::*;
+        match self.0 {
+            Included(ref v) | Excluded(ref v) => Some(v),
+            Unbounded => None,
+        }
+    }
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> StartBound<U> {
+        use Bound::*;
+        StartBound(match self.0 {
+            Included(v) => Included(f(v)),
+            Excluded(v) => Excluded(f(v)),
+            Unbounded => Unbounded,
+        })
+    }
+}
+
+impl<T> EndBound<T> {
+    pub fn value(&self) -> Option<&T> {
+        use Bound::*;
+        match self.0 {
+            Included(ref v) | Excluded(ref v) => Some(v),
+            Unbounded => None,
+        }
+    }
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> EndBound<U> {
+        use Bound::*;
+        EndBound(match self.0 {
+            Included(v) => Included(f(v)),
+            Excluded(v) => Excluded(f(v)),
+            Unbounded => Unbounded,
+        })
+    }
+}
+
 pub(super) trait BorrowPartialOrd<T: ?Sized> {
     fn borrow_partial_cmp(&self, other: &T) -> Option<Ordering>;
 }
@@ -169,6 +208,13 @@ macro __impl($self:ident, $other:ident, $x:ident, $y:ident) {
             })
         }
     }
+
+    #[cfg(feature = "arbitrary")]
+    impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for $self<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            Ok($self(Bound::arbitrary(u)?))
+        }
+    }
 }
 
 __impl!(StartBound, EndBound, Less, Greater);
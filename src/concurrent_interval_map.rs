@@ -0,0 +1,147 @@
+use std::{
+    ops::Deref,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use arc_swap::ArcSwap;
+
+use super::interval::Interval;
+use super::interval_map::IntervalMap;
+
+pub struct ConcurrentIntervalMap<K, V> {
+    current: ArcSwap<IntervalMap<K, V>>,
+    writer: Mutex<()>,
+}
+
+impl<K, V> ConcurrentIntervalMap<K, V> {
+    pub fn new() -> Self {
+        Self::from(IntervalMap::new())
+    }
+    pub fn load(&self) -> Guard<K, V> {
+        Guard(self.current.load())
+    }
+    pub fn snapshot(&self) -> Arc<IntervalMap<K, V>> {
+        self.current.load_full()
+    }
+    pub fn store(&self, map: IntervalMap<K, V>) {
+        let _lock = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        self.current.store(Arc::new(map));
+    }
+    pub fn into_inner(self) -> Arc<IntervalMap<K, V>> {
+        self.current.into_inner()
+    }
+}
+
+impl<K, V> ConcurrentIntervalMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut IntervalMap<K, V>),
+    {
+        let _lock = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut map = IntervalMap::clone(&self.current.load());
+        f(&mut map);
+        self.current.store(Arc::new(map));
+    }
+    pub fn insert<T: Into<Interval<K>>>(&self, key: T, val: V) {
+        self.update(|map| map.insert(key, val))
+    }
+    pub fn overwrite<T: Into<Interval<K>>>(&self, key: T, val: V) {
+        self.update(|map| map.overwrite(key, val))
+    }
+    pub fn remove<T: Into<Interval<K>>>(&self, key: T) {
+        self.update(|map| map.remove(key))
+    }
+}
+
+impl<K, V> Default for ConcurrentIntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> From<IntervalMap<K, V>> for ConcurrentIntervalMap<K, V> {
+    fn from(map: IntervalMap<K, V>) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(map),
+            writer: Mutex::new(()),
+        }
+    }
+}
+
+pub struct Guard<K, V>(arc_swap::Guard<Arc<IntervalMap<K, V>>>);
+
+impl<K, V> Deref for Guard<K, V> {
+    type Target = IntervalMap<K, V>;
+    fn deref(&self) -> &IntervalMap<K, V> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn concurrent_interval_map() {
+        let map = Arc::new(ConcurrentIntervalMap::new());
+        map.overwrite(0..100, 0);
+        let writers: Vec<_> = (1..=4)
+            .map(|n| {
+                let map = map.clone();
+                thread::spawn(move || {
+                    for i in 0..200 {
+                        map.update(|map| {
+                            map.overwrite(0..50, n * 1000 + i);
+                            map.overwrite(50..100, n * 1000 + i);
+                            map.remove(i % 100..=i % 100);
+                            map.insert(i % 100..=i % 100, n * 1000 + i);
+                        });
+                    }
+                })
+            })
+            .collect();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let map = map.clone();
+                thread::spawn(move || {
+                    for _ in 0..2000 {
+                        let guard = map.load();
+                        let v = guard.get(&0).cloned();
+                        assert!(v.is_some());
+                        for k in 0..100 {
+                            assert_eq!(guard.get(&k).cloned(), v);
+                        }
+                        assert!(guard.inner().windows(2).all(|w| w[0].0.end < w[1].0.start));
+                    }
+                })
+            })
+            .collect();
+        for t in writers.into_iter().chain(readers) {
+            t.join().unwrap();
+        }
+        assert_eq!(map.load().len(), 4);
+    }
+
+    #[test]
+    fn poisoned() {
+        let map = ConcurrentIntervalMap::new();
+        map.insert(0..10, 1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            map.update(|map| {
+                map.insert(10..20, 2);
+                panic!();
+            })
+        }));
+        assert!(result.is_err());
+        map.insert(20..30, 3);
+        assert_eq!(
+            *map.snapshot(),
+            std::iter::FromIterator::from_iter(vec![(0..10, 1), (20..30, 3)])
+        );
+    }
+}
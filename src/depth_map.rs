@@ -0,0 +1,245 @@
+use std::{borrow::Borrow, collections::Bound, iter::FromIterator, iter::Peekable, ops::Deref};
+
+use super::bound::{EndBound, StartBound};
+use super::interval::Interval;
+use super::interval_map::{IntervalMap, Iter};
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct DepthMap<K> {
+    map: IntervalMap<K, usize>,
+}
+
+impl<K> DepthMap<K> {
+    pub fn new() -> Self {
+        Self {
+            map: IntervalMap::new(),
+        }
+    }
+    pub fn into_map(self) -> IntervalMap<K, usize> {
+        self.map
+    }
+}
+
+impl<K> DepthMap<K>
+where
+    K: Ord + Clone,
+{
+    fn sweep<I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = (Interval<K>, usize)>,
+    {
+        let mut events = vec![];
+        for (interval, depth) in items {
+            if interval.is_empty() || depth == 0 {
+                continue;
+            }
+            if interval.end.0 != Bound::Unbounded {
+                events.push((StartBound::from(interval.end), -(depth as isize)));
+            }
+            events.push((interval.start, depth as isize));
+        }
+        events.sort_by(|(x, _), (y, _)| x.cmp(y));
+        let mut sorted_vec = vec![];
+        let mut depth = 0;
+        let mut start = None;
+        let mut events = events.into_iter().peekable();
+        while let Some((at, mut delta)) = events.next() {
+            while let Some((_, d)) = events.next_if(|(next, _)| *next == at) {
+                delta += d;
+            }
+            if delta == 0 {
+                continue;
+            }
+            if let Some(start) = start.take() {
+                let end = at.clone().into();
+                sorted_vec.push((Interval { start, end }, depth as usize));
+            }
+            depth += delta;
+            if depth > 0 {
+                start = Some(at);
+            }
+        }
+        if let Some(start) = start {
+            let end = EndBound(Bound::Unbounded);
+            sorted_vec.push((Interval { start, end }, depth as usize));
+        }
+        Self {
+            map: unsafe { IntervalMap::from_inner_unchecked(sorted_vec) },
+        }
+    }
+    fn coalesce(&mut self, interval: &Interval<K>) {
+        let range = self.map.overlapping(interval);
+        let range = range.start.saturating_sub(1)..(range.end + 1).min(self.map.len());
+        let mut entries = self.map.splice(range.clone(), vec![]);
+        entries.dedup_by(|(next, d2), (prev, d1)| {
+            if d1 == d2 && prev.touches(next) {
+                prev.end = next.end.clone();
+                true
+            } else {
+                false
+            }
+        });
+        self.map.splice(range.start..range.start, entries);
+    }
+
+    pub fn add<T: Into<Interval<K>>>(&mut self, key: T) {
+        let interval = key.into();
+        self.map
+            .insert_with(interval.clone(), 1, |depth, n| *depth += n);
+        self.coalesce(&interval);
+    }
+    pub fn subtract<T: Into<Interval<K>>>(&mut self, key: T) {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let range = self.map.overlapping(&interval);
+        let mut entries = vec![];
+        for (i, depth) in self.map.splice(range.clone(), vec![]) {
+            let within = i.intersection(&interval);
+            let [left, right] = i.remove(&interval);
+            entries.extend(left.map(|left| (left, depth)));
+            if depth > 1 {
+                entries.extend(within.map(|within| (within, depth - 1)));
+            }
+            entries.extend(right.map(|right| (right, depth)));
+        }
+        self.map.splice(range.start..range.start, entries);
+        self.coalesce(&interval);
+    }
+    pub fn depth_at<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.get(key).copied().unwrap_or(0)
+    }
+    pub fn max_depth(&self) -> usize {
+        self.map.values().copied().max().unwrap_or(0)
+    }
+    pub fn threshold(&self, n: usize) -> Threshold<K> {
+        Threshold {
+            iter: self.map.iter().peekable(),
+            n,
+        }
+    }
+}
+
+impl<K> Default for DepthMap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Deref for DepthMap<K> {
+    type Target = IntervalMap<K, usize>;
+    fn deref(&self) -> &IntervalMap<K, usize> {
+        &self.map
+    }
+}
+
+impl<K, I> FromIterator<I> for DepthMap<K>
+where
+    K: Ord + Clone,
+    I: Into<Interval<K>>,
+{
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        Self::sweep(iter.into_iter().map(|i| (i.into(), 1)))
+    }
+}
+
+impl<K, I> Extend<I> for DepthMap<K>
+where
+    K: Ord + Clone,
+    I: Into<Interval<K>>,
+{
+    fn extend<T: IntoIterator<Item = I>>(&mut self, iter: T) {
+        let sorted_vec = std::mem::take(&mut self.map).into_inner();
+        *self = Self::sweep(
+            sorted_vec
+                .into_iter()
+                .chain(iter.into_iter().map(|i| (i.into(), 1))),
+        );
+    }
+}
+
+pub struct Threshold<'a, K: 'a> {
+    iter: Peekable<Iter<'a, K, usize>>,
+    n: usize,
+}
+
+impl<'a, K> Iterator for Threshold<'a, K>
+where
+    K: Clone + PartialEq,
+{
+    type Item = Interval<K>;
+    fn next(&mut self) -> Option<Interval<K>> {
+        let n = self.n;
+        let (interval, _) = self.iter.find(|(_, depth)| **depth >= n)?;
+        let mut interval = interval.clone();
+        while let Some((next, _)) = self
+            .iter
+            .next_if(|(next, depth)| **depth >= n && interval.touches(next))
+        {
+            interval.end = next.end.clone();
+        }
+        Some(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_map() {
+        let mut depth = DepthMap::new();
+        depth.add(0..10);
+        depth.add(5..15);
+        depth.add(5..10);
+        depth.add(20..);
+        assert_eq!(depth.depth_at(&-1), 0);
+        assert_eq!(depth.depth_at(&0), 1);
+        assert_eq!(depth.depth_at(&7), 3);
+        assert_eq!(depth.depth_at(&12), 1);
+        assert_eq!(depth.depth_at(&100), 1);
+        assert_eq!(depth.max_depth(), 3);
+        assert_eq!(
+            depth.threshold(2).collect::<Vec<_>>(),
+            vec![Interval::from(5..10)]
+        );
+        assert_eq!(
+            depth.threshold(1).collect::<Vec<_>>(),
+            vec![Interval::from(0..15), Interval::from(20..)]
+        );
+        depth.subtract(5..10);
+        depth.subtract(12..25);
+        assert_eq!(
+            depth.iter().collect::<Vec<_>>(),
+            vec![
+                (&Interval::from(0..5), &1),
+                (&Interval::from(5..10), &2),
+                (&Interval::from(10..12), &1),
+                (&Interval::from(25..), &1),
+            ]
+        );
+        assert_eq!(
+            DepthMap::from_iter(vec![0..10, 5..15, 5..10, 15..20]),
+            DepthMap::from_iter(vec![0..10, 5..15, 5..10, 15..20].into_iter().rev())
+        );
+        let mut swept = DepthMap::from_iter(vec![0..10, 5..15]);
+        swept.extend(vec![5..10, 20..30]);
+        let mut added = DepthMap::new();
+        for i in [0..10, 5..15, 5..10, 20..30] {
+            added.add(i);
+        }
+        assert_eq!(swept, added);
+        assert_eq!(swept.threshold(1).count(), 2);
+        let names = DepthMap::from_iter(vec![
+            String::from("a")..String::from("m"),
+            String::from("f")..String::from("z"),
+        ]);
+        assert_eq!(names.depth_at("g"), 2);
+        assert_eq!(names.depth_at("n"), 1);
+    }
+}
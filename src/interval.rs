@@ -21,6 +21,13 @@ impl<T> Interval<T> {
         }
     }
 
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> Interval<U> {
+        Interval {
+            start: self.start.map(&mut f),
+            end: self.end.map(f),
+        }
+    }
+
     pub fn is_empty(&self) -> bool
     where
         T: PartialOrd,
@@ -65,6 +72,89 @@ impl<T> Interval<T> {
     {
         matches!(self.partial_cmp(other), Some(Ordering::Equal))
     }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self>
+    where
+        T: PartialOrd + Clone,
+    {
+        let interval = Interval {
+            start: if self.start < other.start {
+                other.start.clone()
+            } else {
+                self.start.clone()
+            },
+            end: if self.end < other.end {
+                self.end.clone()
+            } else {
+                other.end.clone()
+            },
+        };
+        if interval.is_empty() {
+            None
+        } else {
+            Some(interval)
+        }
+    }
+
+    pub(super) fn gaps<'a, I>(&self, covered: I) -> Vec<Interval<T>>
+    where
+        T: 'a + PartialOrd + Clone,
+        I: IntoIterator<Item = &'a Interval<T>>,
+    {
+        let mut gaps = vec![];
+        let mut start = self.start.clone();
+        for i in covered {
+            if i.start.0 != Bound::Unbounded {
+                let gap = Interval {
+                    start,
+                    end: i.start.clone().into(),
+                };
+                if !gap.is_empty() {
+                    gaps.push(gap);
+                }
+            }
+            start = match i.end.0 {
+                Bound::Unbounded => return gaps,
+                _ => i.end.clone().into(),
+            };
+        }
+        let gap = Interval {
+            start,
+            end: self.end.clone(),
+        };
+        if !gap.is_empty() {
+            gaps.push(gap);
+        }
+        gaps
+    }
+
+    pub(super) fn touches(&self, next: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        use Bound::*;
+        match (&self.end.0, &next.start.0) {
+            (Included(x), Excluded(y)) | (Excluded(x), Included(y)) => x == y,
+            _ => false,
+        }
+    }
+
+    pub(super) fn is_comparable(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        [self.start.value(), self.end.value()]
+            .iter()
+            .flatten()
+            .all(|v| v.partial_cmp(v).is_some())
+    }
+
+    pub(super) fn includes(&self, other: &Self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.start <= other.start && other.end <= self.end
+    }
 }
 
 impl<T> RangeBounds<T> for Interval<T> {
@@ -170,6 +260,19 @@ impl<T: ToTokens> ToTokens for Interval<T> {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Interval<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Interval {
+            start: StartBound::arbitrary(u)?,
+            end: EndBound::arbitrary(u)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +285,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn map() {
+        let i: Interval<u32> = (1..=5).into();
+        assert_eq!(i.map(|v| v as u64 * 1000), (1000..=5000).into());
+        let i: Interval<i32> = (..5).into();
+        assert_eq!(i.map(|v| v - 1), (..4).into());
+    }
+
+    #[test]
+    fn intersection() {
+        let i: Interval<_> = (0..10).into();
+        assert_eq!(i.intersection(&(5..=15).into()), Some((5..10).into()));
+        assert_eq!(i.intersection(&(..=0).into()), Some((0..=0).into()));
+        assert_eq!(i.intersection(&(10..).into()), None);
+    }
+
     #[cfg(feature = "proc-macro")]
     #[test]
     fn to_tokens() {
@@ -1,7 +1,13 @@
-use std::{borrow::Borrow, collections::Bound, iter::FromIterator};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::{BTreeSet, Bound},
+    iter::FromIterator,
+};
 
 use super::bound::{BorrowPartialOrd2, EndBound, StartBound};
 use super::interval::Interval;
+use super::measure::{Delta, Measure, Midpoint, Snap, Translate};
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 pub struct IntervalMap<K, V> {
@@ -95,7 +101,7 @@ impl<K, V> IntervalMap<K, V> {
         self.sorted_vec.first().map(|(i, v)| (i, v))
     }
     #[cfg(feature = "map_first_last")]
-    pub fn first_entry<T>(&mut self) -> Option<OccupiedEntry<K, V>> {
+    pub fn first_entry(&mut self) -> Option<OccupiedEntry<K, V>> {
         if !self.sorted_vec.is_empty() {
             Some(OccupiedEntry(0, &mut self.sorted_vec))
         } else {
@@ -110,7 +116,7 @@ impl<K, V> IntervalMap<K, V> {
         self.sorted_vec.last().map(|(i, v)| (i, v))
     }
     #[cfg(feature = "map_first_last")]
-    pub fn last_entry<T>(&mut self) -> Option<OccupiedEntry<K, V>> {
+    pub fn last_entry(&mut self) -> Option<OccupiedEntry<K, V>> {
         if !self.sorted_vec.is_empty() {
             Some(OccupiedEntry(
                 self.sorted_vec.len() - 1,
@@ -154,8 +160,8 @@ impl<K, V> IntervalMap<K, V> {
                 interval.end.borrow_partial_cmp_2(&i.end).unwrap()
             }),
         ] {
-            [Ok(s), Ok(e)] | [Err(s), Ok(e)] => Iter(self.sorted_vec[s..=e].iter()),
-            [Ok(s), Err(e)] | [Err(s), Err(e)] => Iter(self.sorted_vec[s..e].iter()),
+            [Ok(s), Ok(e)] | [Err(s), Ok(e)] => Iter(self.sorted_vec[s..(e + 1).max(s)].iter()),
+            [Ok(s), Err(e)] | [Err(s), Err(e)] => Iter(self.sorted_vec[s..e.max(s)].iter()),
         }
     }
 
@@ -174,8 +180,278 @@ impl<K, V> IntervalMap<K, V> {
                 interval.end.borrow_partial_cmp_2(&i.end).unwrap()
             }),
         ] {
-            [Ok(s), Ok(e)] | [Err(s), Ok(e)] => IterMut(self.sorted_vec[s..=e].iter_mut()),
-            [Ok(s), Err(e)] | [Err(s), Err(e)] => IterMut(self.sorted_vec[s..e].iter_mut()),
+            [Ok(s), Ok(e)] | [Err(s), Ok(e)] => {
+                IterMut(self.sorted_vec[s..(e + 1).max(s)].iter_mut())
+            }
+            [Ok(s), Err(e)] | [Err(s), Err(e)] => IterMut(self.sorted_vec[s..e.max(s)].iter_mut()),
+        }
+    }
+
+    /// Maps every bound through `f`, which must be monotone in the given direction.
+    ///
+    /// If `f` is not strictly monotone, a non-empty interval can map to an empty
+    /// one; such entries are dropped. Entries that come to overlap keep the value
+    /// of the entry that comes first in the result.
+    pub fn map_keys_monotone<U, F>(self, mut f: F, monotonicity: Monotonicity) -> IntervalMap<U, V>
+    where
+        U: PartialOrd + Clone,
+        V: Clone,
+        F: FnMut(K) -> U,
+    {
+        let mut map = IntervalMap::with_capacity(self.len());
+        let entries = self.sorted_vec.into_iter().map(|(i, v)| {
+            let interval = match monotonicity {
+                Monotonicity::Increasing => i.map(&mut f),
+                Monotonicity::Decreasing => Interval::new(i.end.0, i.start.0).map(&mut f),
+            };
+            (interval, v)
+        });
+        match monotonicity {
+            Monotonicity::Increasing => entries.for_each(|(i, v)| map.insert_impl(i, v)),
+            Monotonicity::Decreasing => entries.rev().for_each(|(i, v)| map.insert_impl(i, v)),
+        }
+        map
+    }
+
+    pub fn map_values<W, F: FnMut(V) -> W>(self, mut f: F) -> IntervalMap<K, W> {
+        IntervalMap {
+            sorted_vec: self
+                .sorted_vec
+                .into_iter()
+                .map(|(i, v)| (i, f(v)))
+                .collect(),
+        }
+    }
+
+    pub fn try_map_values<W, E, F>(self, mut f: F) -> Result<IntervalMap<K, W>, E>
+    where
+        F: FnMut(V) -> Result<W, E>,
+    {
+        Ok(IntervalMap {
+            sorted_vec: self
+                .sorted_vec
+                .into_iter()
+                .map(|(i, v)| Ok((i, f(v)?)))
+                .collect::<Result<_, E>>()?,
+        })
+    }
+
+    pub fn filter_map_values<W, F: FnMut(V) -> Option<W>>(self, mut f: F) -> IntervalMap<K, W> {
+        IntervalMap {
+            sorted_vec: self
+                .sorted_vec
+                .into_iter()
+                .filter_map(|(i, v)| f(v).map(|w| (i, w)))
+                .collect(),
+        }
+    }
+
+    fn try_search_by<F>(&self, mut f: F) -> Result<Result<usize, usize>, IncomparableKey>
+    where
+        F: FnMut(&Interval<K>) -> Option<Ordering>,
+    {
+        let mut incomparable = false;
+        let found = self.sorted_vec.binary_search_by(|(interval, _)| {
+            f(interval).unwrap_or_else(|| {
+                incomparable = true;
+                Ordering::Equal
+            })
+        });
+        if incomparable {
+            Err(IncomparableKey)
+        } else {
+            Ok(found)
+        }
+    }
+
+    pub fn try_get<Q>(&self, key: &Q) -> Result<Option<&V>, IncomparableKey>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + PartialOrd,
+    {
+        if key.partial_cmp(key).is_none() {
+            return Err(IncomparableKey);
+        }
+        let found = self.try_search_by(|interval| interval.partial_cmp(key))?;
+        Ok(found.ok().map(|i| &self.sorted_vec[i].1))
+    }
+
+    pub fn try_range<T, R>(&self, r: R) -> Result<Iter<K, V>, IncomparableKey>
+    where
+        K: Borrow<T>,
+        R: Into<Interval<T>>,
+        T: PartialOrd,
+    {
+        let i = r.into();
+        if !i.is_comparable() {
+            return Err(IncomparableKey);
+        }
+        let s = self.try_search_by(|interval| interval.start.borrow_partial_cmp_2(&i.start))?;
+        let e = self.try_search_by(|interval| interval.end.borrow_partial_cmp_2(&i.end))?;
+        Ok(match (s, e) {
+            (Ok(s), Ok(e)) | (Err(s), Ok(e)) => Iter(self.sorted_vec[s..(e + 1).max(s)].iter()),
+            (Ok(s), Err(e)) | (Err(s), Err(e)) => Iter(self.sorted_vec[s..e.max(s)].iter()),
+        })
+    }
+
+    fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.sorted_vec
+            .binary_search_by(|(interval, _)| interval.partial_cmp(key).unwrap())
+    }
+
+    pub(super) fn overlapping(&self, interval: &Interval<K>) -> std::ops::Range<usize>
+    where
+        K: Ord,
+    {
+        let lo = self
+            .sorted_vec
+            .partition_point(|(i, _)| i.end < interval.start);
+        let hi = self
+            .sorted_vec
+            .partition_point(|(i, _)| i.start <= interval.end);
+        lo..hi.max(lo)
+    }
+
+    pub fn is_vacant<T: Into<Interval<K>>>(&self, key: T) -> bool
+    where
+        K: Ord,
+    {
+        let interval = key.into();
+        interval.is_empty() || self.overlapping(&interval).is_empty()
+    }
+
+    pub fn conflicts<T: Into<Interval<K>>>(&self, key: T) -> Iter<K, V>
+    where
+        K: Ord,
+    {
+        let interval = key.into();
+        if interval.is_empty() {
+            return Iter([].iter());
+        }
+        Iter(self.sorted_vec[self.overlapping(&interval)].iter())
+    }
+
+    fn gaps(&self, interval: &Interval<K>) -> Vec<Interval<K>>
+    where
+        K: Ord + Clone,
+    {
+        interval.gaps(
+            self.sorted_vec[self.overlapping(interval)]
+                .iter()
+                .map(|(i, _)| i),
+        )
+    }
+
+    pub fn covers<T: Into<Interval<K>>>(&self, window: T) -> bool
+    where
+        K: Ord + Clone,
+    {
+        self.gaps(&window.into()).is_empty()
+    }
+
+    pub fn coverage<T: Into<Interval<K>>>(&self, window: T) -> Coverage<K>
+    where
+        K: Ord + Clone,
+    {
+        let window = window.into();
+        if window.is_empty() {
+            return Coverage {
+                covered: vec![],
+                uncovered: vec![],
+            };
+        }
+        Coverage {
+            covered: self.sorted_vec[self.overlapping(&window)]
+                .iter()
+                .filter_map(|(i, _)| i.intersection(&window))
+                .collect(),
+            uncovered: self.gaps(&window),
+        }
+    }
+
+    pub fn get_interval<T: Into<Interval<K>>>(&self, window: T) -> Option<&V>
+    where
+        K: Ord,
+    {
+        let window = window.into();
+        if window.is_empty() {
+            return None;
+        }
+        match &self.sorted_vec[self.overlapping(&window)] {
+            [(i, v)] if i.includes(&window) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn indexed(&self, i: usize) -> Option<(usize, &Interval<K>, &V)> {
+        self.sorted_vec.get(i).map(|(interval, v)| (i, interval, v))
+    }
+
+    pub fn floor<Q>(&self, key: &Q) -> Option<(usize, &Interval<K>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Ok(i) => self.indexed(i),
+            Err(i) => i.checked_sub(1).and_then(|i| self.indexed(i)),
+        }
+    }
+
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(usize, &Interval<K>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Ok(i) | Err(i) => self.indexed(i),
+        }
+    }
+
+    pub fn prev_entry<Q>(&self, key: &Q) -> Option<(usize, &Interval<K>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Ok(i) | Err(i) => i.checked_sub(1).and_then(|i| self.indexed(i)),
+        }
+    }
+
+    pub fn next_entry<Q>(&self, key: &Q) -> Option<(usize, &Interval<K>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Ok(i) => self.indexed(i + 1),
+            Err(i) => self.indexed(i),
+        }
+    }
+
+    pub fn nearest<Q>(&self, key: &Q) -> Option<(usize, &Interval<K>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord + Measure,
+    {
+        match self.search(key) {
+            Ok(i) => self.indexed(i),
+            Err(i) => match [
+                i.checked_sub(1).and_then(|i| self.indexed(i)),
+                self.indexed(i),
+            ] {
+                [Some(prev), Some(next)] => match (prev.1.end.value(), next.1.start.value()) {
+                    (Some(x), Some(y)) if key.distance(y.borrow()) < key.distance(x.borrow()) => {
+                        Some(next)
+                    }
+                    _ => Some(prev),
+                },
+                [prev, next] => prev.or(next),
+            },
         }
     }
 
@@ -196,6 +472,50 @@ impl<K, V> IntervalMap<K, V> {
         }
     }
 
+    pub fn cursor_front(&self) -> Cursor<K, V> {
+        Cursor(0, &self.sorted_vec)
+    }
+
+    pub fn cursor_back(&self) -> Cursor<K, V> {
+        Cursor(self.len().saturating_sub(1), &self.sorted_vec)
+    }
+
+    pub fn cursor_at<Q>(&self, key: &Q) -> Cursor<K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Ok(i) | Err(i) => Cursor(i, &self.sorted_vec),
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<K, V> {
+        CursorMut(0, &mut self.sorted_vec)
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<K, V> {
+        CursorMut(self.len().saturating_sub(1), &mut self.sorted_vec)
+    }
+
+    pub fn cursor_at_mut<Q>(&mut self, key: &Q) -> CursorMut<K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Ok(i) | Err(i) => CursorMut(i, &mut self.sorted_vec),
+        }
+    }
+
+    pub fn diff<'a>(&'a self, other: &'a Self) -> Diff<'a, K, V>
+    where
+        K: Ord + Clone,
+        V: PartialEq,
+    {
+        Diff(Segments::new(self, other), None)
+    }
+
     pub fn inner(&self) -> &[(Interval<K>, V)] {
         &self.sorted_vec
     }
@@ -207,6 +527,19 @@ impl<K, V> IntervalMap<K, V> {
     pub unsafe fn from_inner_unchecked(inner: Vec<(Interval<K>, V)>) -> Self {
         Self { sorted_vec: inner }
     }
+
+    #[cfg(feature = "rayon")]
+    pub(super) fn inner_mut(&mut self) -> &mut [(Interval<K>, V)] {
+        &mut self.sorted_vec
+    }
+
+    pub(super) fn splice(
+        &mut self,
+        range: std::ops::Range<usize>,
+        entries: Vec<(Interval<K>, V)>,
+    ) -> Vec<(Interval<K>, V)> {
+        self.sorted_vec.splice(range, entries).collect()
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
@@ -216,9 +549,112 @@ pub enum MergedValue<T, U> {
     Both(T, U),
 }
 
+impl<'a, T: Clone, U: Clone> MergedValue<&'a T, &'a U> {
+    fn cloned(self) -> MergedValue<T, U> {
+        match self {
+            MergedValue::Left(x) => MergedValue::Left(x.clone()),
+            MergedValue::Right(y) => MergedValue::Right(y.clone()),
+            MergedValue::Both(x, y) => MergedValue::Both(x.clone(), y.clone()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum DiffSegment<K, V> {
+    Added(Interval<K>, V),
+    Removed(Interval<K>, V),
+    Changed {
+        interval: Interval<K>,
+        old: V,
+        new: V,
+    },
+}
+
+#[derive(Debug)]
+pub enum ConflictPolicy<V> {
+    FirstWins,
+    LastWins,
+    Error,
+    Combine(fn(&V, &V) -> V),
+}
+
+impl<V> Clone for ConflictPolicy<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for ConflictPolicy<V> {}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum SliverPolicy {
+    Previous,
+    Next,
+    Larger,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum SplicePolicy {
+    Previous,
+    Next,
+    Neither,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Monotonicity {
+    Increasing,
+    Decreasing,
+}
+
+pub trait SplitValue<K>: Sized {
+    fn split(self, at: &StartBound<K>, whole: &Interval<K>) -> (Self, Self);
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Conflict<K> {
+    pub interval: Interval<K>,
+    pub sources: Vec<usize>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Coverage<K> {
+    pub covered: Vec<Interval<K>>,
+    pub uncovered: Vec<Interval<K>>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct IncomparableKey;
+
+impl std::fmt::Display for IncomparableKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("key is not comparable")
+    }
+}
+
+impl std::error::Error for IncomparableKey {}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct OverlapError<K, V> {
+    pub interval: Interval<K>,
+    pub value: V,
+    pub conflicts: Vec<(Interval<K>, V)>,
+}
+
+impl<K, V> std::fmt::Display for OverlapError<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "interval overlaps {} existing entries",
+            self.conflicts.len()
+        )
+    }
+}
+
+impl<K: std::fmt::Debug, V: std::fmt::Debug> std::error::Error for OverlapError<K, V> {}
+
 impl<K, V> IntervalMap<K, V>
 where
-    K: Ord + Clone,
+    K: PartialOrd + Clone,
     V: Clone,
 {
     fn insert_impl(&mut self, interval: Interval<K>, val: V) {
@@ -248,8 +684,17 @@ where
             self.sorted_vec.push((interval, val));
         }
     }
-    pub fn insert<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
-        self.insert_impl(key.into(), val)
+    pub fn try_insert<T: Into<Interval<K>>>(
+        &mut self,
+        key: T,
+        val: V,
+    ) -> Result<(), IncomparableKey> {
+        let interval = key.into();
+        if !interval.is_comparable() {
+            return Err(IncomparableKey);
+        }
+        self.insert_impl(interval, val);
+        Ok(())
     }
 
     fn overwrite_impl(&mut self, interval: Interval<K>, val: V) {
@@ -279,9 +724,192 @@ where
             self.sorted_vec.push((interval, val));
         }
     }
+    pub fn try_overwrite<T: Into<Interval<K>>>(
+        &mut self,
+        key: T,
+        val: V,
+    ) -> Result<(), IncomparableKey> {
+        let interval = key.into();
+        if !interval.is_comparable() {
+            return Err(IncomparableKey);
+        }
+        self.overwrite_impl(interval, val);
+        Ok(())
+    }
+
+    fn span(interval: &Interval<K>) -> Option<K::Distance>
+    where
+        K: Measure,
+    {
+        match (interval.start.value(), interval.end.value()) {
+            (Some(start), Some(end)) => Some(start.distance(end)),
+            _ => None,
+        }
+    }
+
+    pub fn remove_shorter_than(&mut self, min_len: K::Distance)
+    where
+        K: Measure,
+    {
+        self.sorted_vec
+            .retain(|(i, _)| Self::span(i).is_none_or(|len| len >= min_len))
+    }
+
+    pub fn absorb_slivers(&mut self, min_len: K::Distance, policy: SliverPolicy)
+    where
+        K: Measure,
+    {
+        let mut i = 0;
+        while i < self.sorted_vec.len() {
+            let interval = &self.sorted_vec[i].0;
+            if Self::span(interval).is_none_or(|len| len >= min_len) {
+                i += 1;
+                continue;
+            }
+            let prev = i
+                .checked_sub(1)
+                .filter(|&p| self.sorted_vec[p].0.touches(interval));
+            let next = Some(i + 1)
+                .filter(|&n| n < self.sorted_vec.len())
+                .filter(|&n| interval.touches(&self.sorted_vec[n].0));
+            let target = match (policy, prev, next) {
+                (SliverPolicy::Previous, prev, _) => prev,
+                (SliverPolicy::Next, _, next) => next,
+                (SliverPolicy::Larger, Some(p), Some(n)) => {
+                    match (
+                        Self::span(&self.sorted_vec[p].0),
+                        Self::span(&self.sorted_vec[n].0),
+                    ) {
+                        (Some(p_len), Some(n_len)) if p_len >= n_len => Some(p),
+                        (None, _) => Some(p),
+                        _ => Some(n),
+                    }
+                }
+                (SliverPolicy::Larger, prev, next) => prev.or(next),
+            };
+            match target {
+                Some(p) if p < i => {
+                    let (sliver, _) = self.sorted_vec.remove(i);
+                    self.sorted_vec[p].0.end = sliver.end;
+                    i = p;
+                }
+                Some(_) => {
+                    let (sliver, _) = self.sorted_vec.remove(i);
+                    self.sorted_vec[i].0.start = sliver.start;
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    pub fn snap_to_grid(&mut self, step: &K)
+    where
+        K: Snap,
+    {
+        for (i, v) in std::mem::take(&mut self.sorted_vec) {
+            self.insert_impl(i.map(|k| k.snap(step)), v);
+        }
+    }
+}
+
+impl<K, V> IntervalMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    pub fn insert<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
+        self.insert_impl(key.into(), val)
+    }
+    pub fn insert_returning<T: Into<Interval<K>>>(&mut self, key: T, val: V) -> Vec<Interval<K>> {
+        let interval = key.into();
+        if interval.is_empty() {
+            return vec![];
+        }
+        let gaps = self.gaps(&interval);
+        self.insert_impl(interval, val);
+        gaps
+    }
+
+    pub fn try_insert_exclusive<T: Into<Interval<K>>>(
+        &mut self,
+        key: T,
+        val: V,
+    ) -> Result<(), OverlapError<K, V>> {
+        let interval = key.into();
+        if interval.is_empty() {
+            return Ok(());
+        }
+        let range = self.overlapping(&interval);
+        if !range.is_empty() {
+            return Err(OverlapError {
+                interval,
+                value: val,
+                conflicts: self.sorted_vec[range].to_vec(),
+            });
+        }
+        self.sorted_vec.insert(range.start, (interval, val));
+        Ok(())
+    }
+    pub fn insert_with<T, F>(&mut self, key: T, val: V, mut f: F)
+    where
+        T: Into<Interval<K>>,
+        F: FnMut(&mut V, &V),
+    {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let range = self.overlapping(&interval);
+        let n = range.start;
+        let entries: Vec<_> = self.sorted_vec.drain(range).collect();
+        let mut gaps = interval
+            .gaps(entries.iter().map(|(i, _)| i))
+            .into_iter()
+            .peekable();
+        let mut merged = vec![];
+        for (i, v) in entries {
+            while let Some(gap) = gaps.next_if(|gap| gap.end < i.start) {
+                merged.push((gap, val.clone()));
+            }
+            let within = i.intersection(&interval);
+            let [left, right] = i.remove(&interval);
+            if let Some(left) = left {
+                merged.push((left, v.clone()));
+            }
+            if let Some(within) = within {
+                let mut v = v.clone();
+                f(&mut v, &val);
+                merged.push((within, v));
+            }
+            if let Some(right) = right {
+                merged.push((right, v));
+            }
+        }
+        merged.extend(gaps.map(|gap| (gap, val.clone())));
+        self.sorted_vec.splice(n..n, merged);
+    }
     pub fn overwrite<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
         self.overwrite_impl(key.into(), val)
     }
+    fn displaced(&self, interval: &Interval<K>) -> Vec<(Interval<K>, V)> {
+        if interval.is_empty() {
+            return vec![];
+        }
+        self.sorted_vec[self.overlapping(interval)]
+            .iter()
+            .filter_map(|(i, v)| i.intersection(interval).map(|i| (i, v.clone())))
+            .collect()
+    }
+    pub fn overwrite_returning<T: Into<Interval<K>>>(
+        &mut self,
+        key: T,
+        val: V,
+    ) -> Vec<(Interval<K>, V)> {
+        let interval = key.into();
+        let displaced = self.displaced(&interval);
+        self.overwrite_impl(interval, val);
+        displaced
+    }
 
     fn remove_impl(&mut self, interval: Interval<K>) {
         if interval.is_empty() {
@@ -309,6 +937,12 @@ where
     pub fn remove<T: Into<Interval<K>>>(&mut self, key: T) {
         self.remove_impl(key.into())
     }
+    pub fn remove_returning<T: Into<Interval<K>>>(&mut self, key: T) -> Vec<(Interval<K>, V)> {
+        let interval = key.into();
+        let displaced = self.displaced(&interval);
+        self.remove_impl(interval);
+        displaced
+    }
     fn append_impl(
         &mut self,
         iter: &mut Drain<K, V>,
@@ -350,234 +984,490 @@ where
                     break None;
                 }
             }
-        } else {
-            y
-        }
+        } else {
+            y
+        }
+    }
+    pub fn append(&mut self, other: &mut Self) {
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return;
+        }
+        let mut iter = other.drain();
+        let x = self.sorted_vec.pop();
+        let y = iter.next();
+        if let Some(v) = self.append_impl(&mut iter, x, y) {
+            self.sorted_vec.push(v);
+        }
+        self.sorted_vec.extend(iter);
+    }
+
+    pub fn from_iter_with_policy<I, T>(
+        iter: I,
+        policy: ConflictPolicy<V>,
+    ) -> Result<Self, Vec<Conflict<K>>>
+    where
+        I: IntoIterator<Item = (T, V)>,
+        T: Into<Interval<K>>,
+    {
+        let (indices, items): (Vec<usize>, Vec<(Interval<K>, V)>) = iter
+            .into_iter()
+            .map(|(i, v)| (i.into(), v))
+            .enumerate()
+            .filter(|(_, (i, _))| !i.is_empty())
+            .unzip();
+        let mut cuts = Self::cuts(&items);
+        cuts.sort();
+        let (map, mut conflicts) = Self::resolve(items, cuts, policy);
+        for conflict in &mut conflicts {
+            for n in &mut conflict.sources {
+                *n = indices[*n];
+            }
+        }
+        if conflicts.is_empty() {
+            Ok(map)
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    pub(super) fn cuts(items: &[(Interval<K>, V)]) -> Vec<StartBound<K>> {
+        let mut cuts = Vec::with_capacity(items.len() * 2);
+        for (i, _) in items {
+            cuts.push(i.start.clone());
+            if i.end.0 != Bound::Unbounded {
+                cuts.push(i.end.clone().into());
+            }
+        }
+        cuts
+    }
+
+    pub(super) fn resolve(
+        items: Vec<(Interval<K>, V)>,
+        mut cuts: Vec<StartBound<K>>,
+        policy: ConflictPolicy<V>,
+    ) -> (Self, Vec<Conflict<K>>) {
+        use ConflictPolicy::*;
+        cuts.dedup();
+        let mut starts = vec![vec![]; cuts.len()];
+        let mut ends = vec![vec![]; cuts.len() + 1];
+        for (n, (i, _)) in items.iter().enumerate() {
+            starts[cuts.binary_search(&i.start).unwrap()].push(n);
+            ends[match i.end.0 {
+                Bound::Unbounded => cuts.len(),
+                _ => cuts.binary_search(&i.end.clone().into()).unwrap(),
+            }]
+            .push(n);
+        }
+        let mut active = BTreeSet::new();
+        let mut owner = None;
+        let mut sorted_vec: Vec<(Interval<K>, V)> = Vec::with_capacity(items.len());
+        let mut conflicts = vec![];
+        for j in 0..cuts.len() {
+            for n in &ends[j] {
+                active.remove(n);
+            }
+            active.extend(&starts[j]);
+            let interval = Interval {
+                start: cuts[j].clone(),
+                end: match cuts.get(j + 1) {
+                    Some(c) => c.clone().into(),
+                    None => EndBound(Bound::Unbounded),
+                },
+            };
+            let n = match (policy, active.len()) {
+                (_, 0) => None,
+                (LastWins, _) => active.iter().next_back().cloned(),
+                (_, 1) | (FirstWins, _) => active.iter().next().cloned(),
+                (Error, _) => {
+                    conflicts.push(Conflict {
+                        interval,
+                        sources: active.iter().cloned().collect(),
+                    });
+                    owner = None;
+                    continue;
+                }
+                (Combine(f), _) => {
+                    let mut it = active.iter().map(|&n| &items[n].1);
+                    let first = it.next().unwrap().clone();
+                    sorted_vec.push((interval, it.fold(first, |acc, v| f(&acc, v))));
+                    owner = None;
+                    continue;
+                }
+            };
+            match n {
+                Some(n) if owner == Some(n) => sorted_vec.last_mut().unwrap().0.end = interval.end,
+                Some(n) => sorted_vec.push((interval, items[n].1.clone())),
+                None => {}
+            }
+            owner = n;
+        }
+        (Self { sorted_vec }, conflicts)
+    }
+
+    pub fn apply_diff<I, W>(&mut self, diff: I)
+    where
+        I: IntoIterator<Item = DiffSegment<K, W>>,
+        W: Borrow<V>,
+    {
+        use DiffSegment::*;
+        for d in diff {
+            match d {
+                Added(interval, new) | Changed { interval, new, .. } => {
+                    self.overwrite_impl(interval, new.borrow().clone())
+                }
+                Removed(interval, _) => self.remove_impl(interval),
+            }
+        }
+    }
+
+    pub fn merge<V2: Clone>(
+        &self,
+        other: &IntervalMap<K, V2>,
+    ) -> IntervalMap<K, MergedValue<V, V2>> {
+        let inner = Segments::new(self, other)
+            .map(|(i, v)| (i, v.cloned()))
+            .collect();
+        unsafe { IntervalMap::from_inner_unchecked(inner) }
+    }
+
+    pub fn split_off(&mut self, key: K) -> Self {
+        self.split_off_at(StartBound(Bound::Included(key)))
+    }
+
+    pub fn split_off_at(&mut self, at: StartBound<K>) -> Self {
+        let i = self
+            .sorted_vec
+            .partition_point(|(interval, _)| interval.end < at);
+        let mut sorted_vec = self.sorted_vec.split_off(i);
+        if let Some((interval, v)) = sorted_vec.first_mut() {
+            if interval.start < at {
+                self.sorted_vec.push((
+                    Interval {
+                        start: std::mem::replace(&mut interval.start, at.clone()),
+                        end: at.into(),
+                    },
+                    v.clone(),
+                ));
+            }
+        }
+        Self { sorted_vec }
+    }
+
+    pub fn split_range<T: Into<Interval<K>>>(mut self, window: T) -> (Self, Self, Self) {
+        let window = window.into();
+        let after = match window.end.0 {
+            Bound::Unbounded => Self::new(),
+            _ => self.split_off_at(window.end.into()),
+        };
+        let within = self.split_off_at(window.start);
+        (self, within, after)
+    }
+
+    pub fn invert(&mut self, val: V) {
+        let gaps = Interval::from(..).gaps(self.keys());
+        self.sorted_vec = gaps.into_iter().map(|i| (i, val.clone())).collect();
+    }
+
+    fn fill_with<F>(&mut self, max_gap: K::Distance, mut f: F)
+    where
+        K: Measure,
+        F: FnMut(&mut Interval<K>, &mut Interval<K>),
+    {
+        for i in 1..self.sorted_vec.len() {
+            let (x, y) = self.sorted_vec.split_at_mut(i);
+            let (prev, next) = (&mut x[i - 1].0, &mut y[0].0);
+            if prev.touches(next) {
+                continue;
+            }
+            if let (Some(end), Some(start)) = (prev.end.value(), next.start.value()) {
+                if end.distance(start) <= max_gap {
+                    f(prev, next);
+                }
+            }
+        }
+    }
+
+    pub fn forward_fill(&mut self, max_gap: K::Distance)
+    where
+        K: Measure,
+    {
+        self.fill_with(max_gap, |prev, next| {
+            prev.end = next.start.clone().into();
+        })
+    }
+
+    pub fn backward_fill(&mut self, max_gap: K::Distance)
+    where
+        K: Measure,
+    {
+        self.fill_with(max_gap, |prev, next| {
+            next.start = prev.end.clone().into();
+        })
+    }
+
+    pub fn fill_to_nearest(&mut self, max_gap: K::Distance)
+    where
+        K: Midpoint,
+    {
+        self.fill_with(max_gap, |prev, next| {
+            if let (Some(end), Some(start)) = (prev.end.value(), next.start.value()) {
+                let midpoint = EndBound(Bound::Excluded(end.midpoint(start)));
+                if prev.end < midpoint {
+                    prev.end = midpoint;
+                }
+                next.start = prev.end.clone().into();
+            }
+        })
     }
-    pub fn append(&mut self, other: &mut Self) {
-        if self.is_empty() {
-            std::mem::swap(self, other);
-            return;
+
+    fn shift_where<S, E>(
+        &mut self,
+        delta: &Delta<K::Distance>,
+        mut shift_start: S,
+        mut shift_end: E,
+    ) where
+        K: Translate,
+        S: FnMut(&K, &Bound<K>) -> bool,
+        E: FnMut(&K, &Bound<K>) -> bool,
+    {
+        fn shift<K, F>(bound: Bound<K>, delta: &Delta<K::Distance>, mut f: F) -> Bound<K>
+        where
+            K: Translate,
+            F: FnMut(&K, &Bound<K>) -> bool,
+        {
+            match bound {
+                Bound::Included(ref v) if f(v, &bound) => Bound::Included(v.translate(delta)),
+                Bound::Excluded(ref v) if f(v, &bound) => Bound::Excluded(v.translate(delta)),
+                bound => bound,
+            }
         }
-        let mut iter = other.drain();
-        let x = self.sorted_vec.pop();
-        let y = iter.next();
-        if let Some(v) = self.append_impl(&mut iter, x, y) {
-            self.sorted_vec.push(v);
+        for (i, v) in std::mem::take(&mut self.sorted_vec) {
+            let interval = Interval::new(
+                shift(i.start.0, delta, &mut shift_start),
+                shift(i.end.0, delta, &mut shift_end),
+            );
+            self.insert_impl(interval, v);
         }
-        self.sorted_vec.extend(iter);
     }
 
-    pub fn merge<V2: Clone>(
-        &self,
-        other: &IntervalMap<K, V2>,
-    ) -> IntervalMap<K, MergedValue<V, V2>> {
-        if other.is_empty() {
-            let inner = self
-                .iter()
-                .map(|(i, v)| (i.clone(), MergedValue::Left(v.clone())))
-                .collect();
-            unsafe { IntervalMap::from_inner_unchecked(inner) }
-        } else if self.is_empty() {
-            let inner = other
-                .iter()
-                .map(|(i, v)| (i.clone(), MergedValue::Right(v.clone())))
-                .collect();
-            unsafe { IntervalMap::from_inner_unchecked(inner) }
-        } else {
-            let mut inner = Vec::with_capacity((self.len() + other.len()) * 2 - 1);
-            let mut it1 = self.iter().map(|(i, v)| (i.clone(), v.clone()));
-            let mut it2 = other.iter().map(|(i, v)| (i.clone(), v.clone()));
-            let mut x1 = it1.next();
-            let mut x2 = it2.next();
-            loop {
-                let x = (x1, x2);
-                if let (Some((i1, v1)), Some((i2, v2))) = x {
-                    if i1.end < i2.start {
-                        inner.push((i1, MergedValue::Left(v1)));
-                        x2 = Some((i2, v2));
-                        x1 = it1.next();
-                    } else if i2.end < i1.start {
-                        inner.push((i2, MergedValue::Right(v2)));
-                        x1 = Some((i1, v1));
-                        x2 = it2.next();
-                    } else if i1.start < i2.start {
-                        inner.push((
-                            Interval {
-                                start: i1.start,
-                                end: i2.start.clone().into(),
-                            },
-                            MergedValue::Left(v1.clone()),
-                        ));
-                        x1 = Some((
-                            Interval {
-                                start: i2.start.clone(),
-                                end: i1.end,
-                            },
-                            v1,
-                        ));
-                        x2 = Some((i2, v2));
-                    } else if i2.start < i1.start {
-                        inner.push((
-                            Interval {
-                                start: i2.start,
-                                end: i1.start.clone().into(),
-                            },
-                            MergedValue::Right(v2.clone()),
-                        ));
-                        x2 = Some((
-                            Interval {
-                                start: i1.start.clone(),
-                                end: i2.end,
-                            },
-                            v2,
-                        ));
-                        x1 = Some((i1, v1));
-                    } else if i1.end < i2.end {
-                        inner.push((
-                            Interval {
-                                start: i1.start,
-                                end: i1.end.clone(),
-                            },
-                            MergedValue::Both(v1, v2.clone()),
-                        ));
-                        x2 = Some((
-                            Interval {
-                                start: i1.end.into(),
-                                end: i2.end,
-                            },
-                            v2,
-                        ));
-                        x1 = it1.next();
-                    } else if i2.end < i1.end {
-                        inner.push((
-                            Interval {
-                                start: i2.start,
-                                end: i2.end.clone(),
-                            },
-                            MergedValue::Both(v1.clone(), v2),
-                        ));
-                        x1 = Some((
-                            Interval {
-                                start: i2.end.into(),
-                                end: i1.end,
-                            },
-                            v1,
-                        ));
-                        x2 = it2.next();
-                    } else {
-                        inner.push((
-                            Interval {
-                                start: i1.start,
-                                end: i1.end,
-                            },
-                            MergedValue::Both(v1, v2),
-                        ));
-                        x1 = it1.next();
-                        x2 = it2.next();
-                    }
-                } else {
-                    x1 = x.0;
-                    x2 = x.1;
-                    break;
-                }
-            }
-            if let Some((i, v)) = x1 {
-                inner.push((i, MergedValue::Left(v)))
+    /// Shifts every bound at or after `point` by `delta`.
+    ///
+    /// A backward shift can collapse entries that straddle `point` to empty
+    /// intervals; those entries are dropped. Where shifted entries overlap
+    /// unshifted ones, the unshifted entries win. Use `splice_domain` to
+    /// delete a range of the domain instead.
+    pub fn shift_from(&mut self, point: &K, delta: Delta<K::Distance>)
+    where
+        K: Translate,
+    {
+        self.shift_where(&delta, |v, _| v >= point, |v, _| v >= point)
+    }
+
+    pub fn splice_domain(
+        &mut self,
+        at: K,
+        removed_len: K::Distance,
+        inserted_len: K::Distance,
+        policy: SplicePolicy,
+    ) where
+        K: Translate,
+    {
+        let removed = Delta::Forward(removed_len);
+        let end = at.translate(&removed);
+        let back = removed.reverse();
+        let clamp = |bound: Bound<K>, within: fn(K) -> Bound<K>| match bound {
+            Bound::Included(v) | Bound::Excluded(v) if at <= v && v < end => within(at.clone()),
+            Bound::Included(v) if v >= end => Bound::Included(v.translate(&back)),
+            Bound::Excluded(v) if v >= end => Bound::Excluded(v.translate(&back)),
+            bound => bound,
+        };
+        self.sorted_vec = std::mem::take(&mut self.sorted_vec)
+            .into_iter()
+            .map(|(i, v)| {
+                let start = clamp(i.start.0, Bound::Included);
+                (Interval::new(start, clamp(i.end.0, Bound::Excluded)), v)
+            })
+            .filter(|(i, _)| !i.is_empty())
+            .collect();
+        self.shift_where(
+            &Delta::Forward(inserted_len),
+            |v, _| *v > at || *v == at && policy != SplicePolicy::Next,
+            |v, bound| match bound {
+                Bound::Excluded(_) if *v == at => policy == SplicePolicy::Previous,
+                _ => *v >= at,
+            },
+        )
+    }
+
+    fn neighbours_of(&self, gap: &Interval<K>) -> (usize, [Option<&V>; 2]) {
+        let n = self.sorted_vec.partition_point(|(i, _)| i.end < gap.start);
+        let prev = n.checked_sub(1).map(|i| &self.sorted_vec[i].1);
+        (n, [prev, self.sorted_vec.get(n).map(|(_, v)| v)])
+    }
+
+    pub fn fill_gaps<T, F>(&mut self, window: T, mut f: F)
+    where
+        T: Into<Interval<K>>,
+        F: FnMut(&Interval<K>, Option<&V>, Option<&V>) -> Option<V>,
+    {
+        let window = window.into();
+        if window.is_empty() {
+            return;
+        }
+        let mut fills = vec![];
+        for gap in self.gaps(&window) {
+            let (n, [prev, next]) = self.neighbours_of(&gap);
+            if let Some(v) = f(&gap, prev, next) {
+                fills.push((n, (gap, v)));
             }
-            if let Some((i, v)) = x2 {
-                inner.push((i, MergedValue::Right(v)))
+        }
+        if fills.is_empty() {
+            return;
+        }
+        let old = std::mem::take(&mut self.sorted_vec);
+        self.sorted_vec.reserve(old.len() + fills.len());
+        let mut fills = fills.into_iter().peekable();
+        for (i, entry) in old.into_iter().enumerate() {
+            while let Some((_, fill)) = fills.next_if(|(n, _)| *n == i) {
+                self.sorted_vec.push(fill);
             }
-            inner.extend(it1.map(|(i, v)| (i, MergedValue::Left(v))));
-            inner.extend(it2.map(|(i, v)| (i, MergedValue::Right(v))));
-            unsafe { IntervalMap::from_inner_unchecked(inner) }
+            self.sorted_vec.push(entry);
         }
+        self.sorted_vec.extend(fills.map(|(_, fill)| fill));
     }
 
-    pub fn split_off<Q>(&mut self, key: K) -> Self {
-        match self
-            .sorted_vec
-            .binary_search_by(|(interval, _)| interval.partial_cmp(&key).unwrap())
-        {
-            Ok(i) => {
-                let v = &mut self.sorted_vec[i];
-                if v.0.start == key {
-                    Self {
-                        sorted_vec: self.sorted_vec.split_off(i),
-                    }
-                } else {
-                    let mut sorted_vec = self.sorted_vec.split_off(i);
-                    self.sorted_vec.push((
-                        Interval {
-                            start: std::mem::replace(
-                                &mut sorted_vec[0].0.start,
-                                StartBound(Bound::Included(key.clone())),
-                            ),
-                            end: EndBound(Bound::Excluded(key)),
-                        },
-                        sorted_vec[0].1.clone(),
-                    ));
-                    Self { sorted_vec }
-                }
-            }
-            Err(i) => Self {
-                sorted_vec: self.sorted_vec.split_off(i),
-            },
-        }
+    pub fn complement_map<W, F>(&self, mut f: F) -> IntervalMap<K, W>
+    where
+        F: FnMut(&Interval<K>, Option<&V>, Option<&V>) -> W,
+    {
+        let sorted_vec = Interval::from(..)
+            .gaps(self.keys())
+            .into_iter()
+            .map(|gap| {
+                let (_, [prev, next]) = self.neighbours_of(&gap);
+                let w = f(&gap, prev, next);
+                (gap, w)
+            })
+            .collect();
+        IntervalMap { sorted_vec }
     }
+}
 
-    pub fn invert(&mut self, val: V) {
-        use std::mem::replace;
-        use Bound::*;
-        if self.is_empty() {
-            self.sorted_vec.push(((..).into(), val));
+impl<K, V> IntervalMap<K, V>
+where
+    K: Ord + Clone,
+    V: SplitValue<K>,
+{
+    fn split_entry(
+        (interval, val): (Interval<K>, V),
+        at: StartBound<K>,
+    ) -> ((Interval<K>, V), (Interval<K>, V)) {
+        let (left, right) = val.split(&at, &interval);
+        let Interval { start, end } = interval;
+        (
+            (
+                Interval {
+                    start,
+                    end: at.clone().into(),
+                },
+                left,
+            ),
+            (Interval { start: at, end }, right),
+        )
+    }
+    fn carve(entry: (Interval<K>, V), window: &Interval<K>) -> [Option<(Interval<K>, V)>; 2] {
+        let (before, rest) = if entry.0.start < window.start {
+            let (before, rest) = Self::split_entry(entry, window.start.clone());
+            (Some(before), rest)
+        } else {
+            (None, entry)
+        };
+        let after = if rest.0.end > window.end {
+            Some(Self::split_entry(rest, window.end.clone().into()).1)
+        } else {
+            None
+        };
+        [before, after]
+    }
+    fn take_overlapping(&mut self, interval: &Interval<K>) -> (usize, Vec<(Interval<K>, V)>) {
+        let range = self.overlapping(interval);
+        (range.start, self.sorted_vec.drain(range).collect())
+    }
+
+    pub fn insert_splitting<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
+        let interval = key.into();
+        if interval.is_empty() {
             return;
         }
-        let inner = &mut self.sorted_vec;
-        let mut prev = Unbounded;
-        let mut n = 0;
-        for i in 0..inner.len() {
-            if inner[i].0.start.0 == prev {
-                prev = replace(&mut inner[i].0.end, EndBound(Unbounded)).0;
-            } else if i == n {
-                let interval = &mut inner[n].0;
-                prev = StartBound::from(replace(
-                    &mut interval.end,
-                    replace(
-                        &mut interval.start,
-                        StartBound(replace(&mut prev, Unbounded)),
-                    )
-                    .into(),
-                ))
-                .0;
-                inner[n].1 = val.clone();
-                n += 1;
-            } else {
-                {
-                    let (x, y) = inner.split_at_mut(i);
-                    let x = &mut x[n].0;
-                    let y = &mut y[0].0;
-                    prev = StartBound::from(replace(
-                        &mut y.end,
-                        replace(
-                            &mut x.end,
-                            replace(
-                                &mut y.start,
-                                replace(&mut x.start, StartBound(replace(&mut prev, Unbounded))),
-                            )
-                            .into(),
-                        ),
-                    ))
-                    .0;
+        let (n, entries) = self.take_overlapping(&interval);
+        let mut merged = vec![];
+        let mut rest = Some((interval, val));
+        for (existing, v) in entries {
+            if let Some(mut r) = rest.take() {
+                if r.0.start < existing.start {
+                    let (gap, right) = Self::split_entry(r, existing.start.clone());
+                    merged.push(gap);
+                    r = right;
+                }
+                if r.0.end > existing.end {
+                    rest = Some(Self::split_entry(r, existing.end.clone().into()).1);
                 }
-                inner[n].1 = val.clone();
-                n += 1;
             }
+            merged.push((existing, v));
+        }
+        merged.extend(rest);
+        self.sorted_vec.splice(n..n, merged);
+    }
+    pub fn overwrite_splitting<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let (n, entries) = self.take_overlapping(&interval);
+        let (mut before, mut after) = (None, None);
+        for entry in entries {
+            let [b, a] = Self::carve(entry, &interval);
+            before = before.or(b);
+            after = a.or(after);
+        }
+        let merged = before
+            .into_iter()
+            .chain(std::iter::once((interval, val)))
+            .chain(after);
+        self.sorted_vec.splice(n..n, merged);
+    }
+    pub fn remove_splitting<T: Into<Interval<K>>>(&mut self, key: T) {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
         }
-        inner.truncate(n);
-        if prev != Unbounded {
-            inner.push((Interval::new(prev, Unbounded), val));
+        let (n, entries) = self.take_overlapping(&interval);
+        let mut kept = vec![];
+        for entry in entries {
+            let [before, after] = Self::carve(entry, &interval);
+            kept.extend(before);
+            kept.extend(after);
+        }
+        self.sorted_vec.splice(n..n, kept);
+    }
+    pub fn split_off_splitting(&mut self, key: K) -> Self {
+        self.split_off_at_splitting(StartBound(Bound::Included(key)))
+    }
+    pub fn split_off_at_splitting(&mut self, at: StartBound<K>) -> Self {
+        let i = self
+            .sorted_vec
+            .partition_point(|(interval, _)| interval.end < at);
+        let mut sorted_vec = self.sorted_vec.split_off(i);
+        if sorted_vec
+            .first()
+            .is_some_and(|(interval, _)| interval.start < at)
+        {
+            let (left, right) = Self::split_entry(sorted_vec.remove(0), at);
+            self.sorted_vec.push(left);
+            sorted_vec.insert(0, right);
         }
+        Self { sorted_vec }
     }
 }
 
@@ -669,6 +1559,192 @@ impl<'a, K: 'a, V: 'a> Iterator for Drain<'a, K, V> {
     }
 }
 
+// Values are borrowed, but intervals are owned: a segment cut at the other
+// map's boundary ends at the complement of that map's start bound, which
+// exists in neither map and so cannot be borrowed from it.
+struct Segments<'a, K: 'a, V: 'a, V2: 'a> {
+    it1: std::slice::Iter<'a, (Interval<K>, V)>,
+    it2: std::slice::Iter<'a, (Interval<K>, V2)>,
+    x1: Option<(Interval<K>, &'a V)>,
+    x2: Option<(Interval<K>, &'a V2)>,
+}
+
+impl<'a, K, V, V2> Segments<'a, K, V, V2>
+where
+    K: Clone,
+{
+    fn new(map1: &'a IntervalMap<K, V>, map2: &'a IntervalMap<K, V2>) -> Self {
+        let mut it1 = map1.sorted_vec.iter();
+        let mut it2 = map2.sorted_vec.iter();
+        let x1 = it1.next().map(|(i, v)| (i.clone(), v));
+        let x2 = it2.next().map(|(i, v)| (i.clone(), v));
+        Self { it1, it2, x1, x2 }
+    }
+    fn next1(&mut self) -> Option<(Interval<K>, &'a V)> {
+        self.it1.next().map(|(i, v)| (i.clone(), v))
+    }
+    fn next2(&mut self) -> Option<(Interval<K>, &'a V2)> {
+        self.it2.next().map(|(i, v)| (i.clone(), v))
+    }
+}
+
+impl<'a, K, V, V2> Iterator for Segments<'a, K, V, V2>
+where
+    K: Ord + Clone,
+{
+    type Item = (Interval<K>, MergedValue<&'a V, &'a V2>);
+    fn next(&mut self) -> Option<Self::Item> {
+        use MergedValue::*;
+        match (self.x1.take(), self.x2.take()) {
+            (None, None) => None,
+            (Some((i1, v1)), None) => {
+                self.x1 = self.next1();
+                Some((i1, Left(v1)))
+            }
+            (None, Some((i2, v2))) => {
+                self.x2 = self.next2();
+                Some((i2, Right(v2)))
+            }
+            (Some((i1, v1)), Some((i2, v2))) => {
+                if i1.end < i2.start {
+                    self.x1 = self.next1();
+                    self.x2 = Some((i2, v2));
+                    Some((i1, Left(v1)))
+                } else if i2.end < i1.start {
+                    self.x1 = Some((i1, v1));
+                    self.x2 = self.next2();
+                    Some((i2, Right(v2)))
+                } else if i1.start < i2.start {
+                    self.x1 = Some((
+                        Interval {
+                            start: i2.start.clone(),
+                            end: i1.end,
+                        },
+                        v1,
+                    ));
+                    let end = i2.start.clone().into();
+                    self.x2 = Some((i2, v2));
+                    Some((
+                        Interval {
+                            start: i1.start,
+                            end,
+                        },
+                        Left(v1),
+                    ))
+                } else if i2.start < i1.start {
+                    self.x2 = Some((
+                        Interval {
+                            start: i1.start.clone(),
+                            end: i2.end,
+                        },
+                        v2,
+                    ));
+                    let end = i1.start.clone().into();
+                    self.x1 = Some((i1, v1));
+                    Some((
+                        Interval {
+                            start: i2.start,
+                            end,
+                        },
+                        Right(v2),
+                    ))
+                } else if i1.end < i2.end {
+                    self.x1 = self.next1();
+                    self.x2 = Some((
+                        Interval {
+                            start: i1.end.clone().into(),
+                            end: i2.end,
+                        },
+                        v2,
+                    ));
+                    Some((i1, Both(v1, v2)))
+                } else if i2.end < i1.end {
+                    self.x1 = Some((
+                        Interval {
+                            start: i2.end.clone().into(),
+                            end: i1.end,
+                        },
+                        v1,
+                    ));
+                    self.x2 = self.next2();
+                    Some((i2, Both(v1, v2)))
+                } else {
+                    self.x1 = self.next1();
+                    self.x2 = self.next2();
+                    Some((i1, Both(v1, v2)))
+                }
+            }
+        }
+    }
+}
+
+pub struct Diff<'a, K: 'a, V: 'a>(Segments<'a, K, V, V>, Option<DiffSegment<K, &'a V>>);
+
+impl<'a, K, V> Iterator for Diff<'a, K, V>
+where
+    K: Ord + Clone,
+    V: PartialEq,
+{
+    type Item = DiffSegment<K, &'a V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        use DiffSegment::*;
+        use MergedValue::*;
+        for (interval, v) in &mut self.0 {
+            let d = match v {
+                Left(old) => Removed(interval, old),
+                Right(new) => Added(interval, new),
+                Both(old, new) if old == new => match self.1.take() {
+                    None => continue,
+                    d => return d,
+                },
+                Both(old, new) => Changed { interval, old, new },
+            };
+            let d = match (self.1.take(), d) {
+                (Some(Added(i1, v1)), Added(i2, v2)) if v1 == v2 && i1.touches(&i2) => Added(
+                    Interval {
+                        start: i1.start,
+                        end: i2.end,
+                    },
+                    v1,
+                ),
+                (Some(Removed(i1, v1)), Removed(i2, v2)) if v1 == v2 && i1.touches(&i2) => Removed(
+                    Interval {
+                        start: i1.start,
+                        end: i2.end,
+                    },
+                    v1,
+                ),
+                (
+                    Some(Changed {
+                        interval: i1,
+                        old: o1,
+                        new: n1,
+                    }),
+                    Changed {
+                        interval: i2,
+                        old: o2,
+                        new: n2,
+                    },
+                ) if o1 == o2 && n1 == n2 && i1.touches(&i2) => Changed {
+                    interval: Interval {
+                        start: i1.start,
+                        end: i2.end,
+                    },
+                    old: o1,
+                    new: n1,
+                },
+                (Some(prev), d) => {
+                    self.1 = Some(d);
+                    return Some(prev);
+                }
+                (None, d) => d,
+            };
+            self.1 = Some(d);
+        }
+        self.1.take()
+    }
+}
+
 #[derive(Debug)]
 pub enum Entry<'a, K: 'a, V: 'a> {
     Vacant(VacantEntry<'a, K, V>),
@@ -730,54 +1806,313 @@ where
 }
 
 #[derive(Debug)]
-pub struct VacantEntry<'a, K: 'a, V: 'a>(
-    usize,
-    Interval<K>,
-    &'a mut std::vec::Vec<(Interval<K>, V)>,
-);
+pub struct VacantEntry<'a, K: 'a, V: 'a>(
+    usize,
+    Interval<K>,
+    &'a mut std::vec::Vec<(Interval<K>, V)>,
+);
+
+impl<'a, K, V> VacantEntry<'a, K, V>
+where
+    K: Ord,
+{
+    pub fn key(&self) -> &Interval<K> {
+        &self.1
+    }
+    pub fn into_key(self) -> Interval<K> {
+        self.1
+    }
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.2.insert(self.0, (self.1, value));
+        &mut self.2[self.0].1
+    }
+    pub fn gap(&self) -> Interval<K>
+    where
+        K: Clone,
+    {
+        Interval {
+            start: match self.0 {
+                0 => StartBound(Bound::Unbounded),
+                i => self.2[i - 1].0.end.clone().into(),
+            },
+            end: match self.2.get(self.0) {
+                Some((interval, _)) => interval.start.clone().into(),
+                None => EndBound(Bound::Unbounded),
+            },
+        }
+    }
+    pub fn insert_gap(self, value: V) -> &'a mut V
+    where
+        K: Clone,
+    {
+        let gap = self.gap();
+        self.2.insert(self.0, (gap, value));
+        &mut self.2[self.0].1
+    }
+    pub fn insert_within<T: Into<Interval<K>>>(
+        self,
+        key: T,
+        value: V,
+    ) -> Result<&'a mut V, (Interval<K>, V)>
+    where
+        K: Clone,
+    {
+        let interval = key.into();
+        let gap = self.gap();
+        if interval.is_empty() || !gap.includes(&interval) {
+            return Err((interval, value));
+        }
+        self.2.insert(self.0, (interval, value));
+        Ok(&mut self.2[self.0].1)
+    }
+}
+
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, K: 'a, V: 'a>(usize, &'a mut std::vec::Vec<(Interval<K>, V)>);
+impl<'a, K, V> OccupiedEntry<'a, K, V>
+where
+    K: Ord,
+{
+    pub fn key(&self) -> &Interval<K> {
+        &self.1[self.0].0
+    }
+    pub fn remove_entry(self) -> (Interval<K>, V) {
+        self.1.remove(self.0)
+    }
+    pub fn get(&self) -> &V {
+        &self.1[self.0].1
+    }
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.1[self.0].1
+    }
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.1[self.0].1
+    }
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+    pub fn neighbors(&self) -> [Option<(&Interval<K>, &V)>; 2] {
+        [
+            match self.0 {
+                0 => None,
+                i => self.1.get(i - 1).map(|(i, v)| (i, v)),
+            },
+            self.1.get(self.0 + 1).map(|(i, v)| (i, v)),
+        ]
+    }
+    pub fn split_at(&mut self, at: StartBound<K>) -> Result<(), StartBound<K>>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        if at.0 == Bound::Unbounded {
+            return Err(at);
+        }
+        let (interval, v) = &self.1[self.0];
+        let left = Interval {
+            start: interval.start.clone(),
+            end: at.clone().into(),
+        };
+        let right = Interval {
+            start: at,
+            end: interval.end.clone(),
+        };
+        if left.is_empty() || right.is_empty() {
+            return Err(right.start);
+        }
+        let v = v.clone();
+        self.1[self.0].0 = left;
+        self.1.insert(self.0 + 1, (right, v));
+        Ok(())
+    }
+    pub fn shrink_to<T: Into<Interval<K>>>(&mut self, key: T) -> Result<(), Interval<K>> {
+        let interval = key.into();
+        if interval.is_empty() || !self.key().includes(&interval) {
+            return Err(interval);
+        }
+        self.1[self.0].0 = interval;
+        Ok(())
+    }
+    pub fn extend_to<T: Into<Interval<K>>>(&mut self, key: T) -> Result<(), Interval<K>> {
+        let interval = key.into();
+        if !interval.includes(self.key()) {
+            return Err(interval);
+        }
+        match self.neighbors() {
+            [Some((prev, _)), _] if interval.start <= prev.end => return Err(interval),
+            [_, Some((next, _))] if next.start <= interval.end => return Err(interval),
+            _ => {}
+        }
+        self.1[self.0].0 = interval;
+        Ok(())
+    }
+    pub fn remove_part<T: Into<Interval<K>>>(self, key: T)
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let i = self.0;
+        match self.1[i].0.clone().remove(&interval) {
+            [Some(left), Some(right)] => {
+                let v = self.1[i].1.clone();
+                self.1[i].0 = left;
+                self.1.insert(i + 1, (right, v));
+            }
+            [Some(left), None] => {
+                self.1[i].0 = left;
+            }
+            [None, Some(right)] => {
+                self.1[i].0 = right;
+            }
+            [None, None] => {
+                self.1.remove(i);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Cursor<'a, K: 'a, V: 'a>(usize, &'a [(Interval<K>, V)]);
+
+impl<'a, K, V> Cursor<'a, K, V> {
+    pub fn index(&self) -> Option<usize> {
+        if self.0 < self.1.len() {
+            Some(self.0)
+        } else {
+            None
+        }
+    }
+    pub fn move_next(&mut self) {
+        self.0 = if self.0 < self.1.len() { self.0 + 1 } else { 0 };
+    }
+    pub fn move_prev(&mut self) {
+        self.0 = match self.0 {
+            0 => self.1.len(),
+            i => i - 1,
+        };
+    }
+    pub fn current(&self) -> Option<(&'a Interval<K>, &'a V)> {
+        self.1.get(self.0).map(|(i, v)| (i, v))
+    }
+    pub fn peek_next(&self) -> Option<(&'a Interval<K>, &'a V)> {
+        if self.0 < self.1.len() {
+            self.1.get(self.0 + 1)
+        } else {
+            self.1.first()
+        }
+        .map(|(i, v)| (i, v))
+    }
+    pub fn peek_prev(&self) -> Option<(&'a Interval<K>, &'a V)> {
+        match self.0 {
+            0 => None,
+            i => self.1.get(i - 1).map(|(i, v)| (i, v)),
+        }
+    }
+}
+
+impl<'a, K, V> Clone for Cursor<'a, K, V> {
+    fn clone(&self) -> Self {
+        Cursor(self.0, self.1)
+    }
+}
+
+#[derive(Debug)]
+pub struct CursorMut<'a, K: 'a, V: 'a>(usize, &'a mut std::vec::Vec<(Interval<K>, V)>);
 
-impl<'a, K, V> VacantEntry<'a, K, V>
-where
-    K: Ord,
-{
-    pub fn key(&self) -> &Interval<K> {
-        &self.1
+impl<'a, K, V> CursorMut<'a, K, V> {
+    pub fn index(&self) -> Option<usize> {
+        self.as_cursor().index()
     }
-    pub fn into_key(self) -> Interval<K> {
-        self.1
+    pub fn move_next(&mut self) {
+        self.0 = if self.0 < self.1.len() { self.0 + 1 } else { 0 };
     }
-    pub fn insert(self, value: V) -> &'a mut V {
-        self.2.insert(self.0, (self.1, value));
-        &mut self.2[self.0].1
+    pub fn move_prev(&mut self) {
+        self.0 = match self.0 {
+            0 => self.1.len(),
+            i => i - 1,
+        };
     }
-}
-
-#[derive(Debug)]
-pub struct OccupiedEntry<'a, K: 'a, V: 'a>(usize, &'a mut std::vec::Vec<(Interval<K>, V)>);
-impl<'a, K, V> OccupiedEntry<'a, K, V>
-where
-    K: Ord,
-{
-    pub fn key(&self) -> &Interval<K> {
-        &self.1[self.0].0
+    pub fn current(&self) -> Option<(&Interval<K>, &V)> {
+        self.1.get(self.0).map(|(i, v)| (i, v))
     }
-    pub fn remove_entry(self) -> (Interval<K>, V) {
-        self.1.remove(self.0)
+    pub fn current_mut(&mut self) -> Option<(&Interval<K>, &mut V)> {
+        self.1.get_mut(self.0).map(|(i, v)| (&*i, v))
     }
-    pub fn get(&self) -> &V {
-        &self.1[self.0].1
+    pub fn peek_next(&self) -> Option<(&Interval<K>, &V)> {
+        self.as_cursor().peek_next()
     }
-    pub fn get_mut(&mut self) -> &mut V {
-        &mut self.1[self.0].1
+    pub fn peek_prev(&self) -> Option<(&Interval<K>, &V)> {
+        self.as_cursor().peek_prev()
     }
-    pub fn into_mut(self) -> &'a mut V {
-        &mut self.1[self.0].1
+    pub fn as_cursor(&self) -> Cursor<K, V> {
+        Cursor(self.0, self.1)
     }
-    pub fn insert(&mut self, value: V) -> V {
-        std::mem::replace(self.get_mut(), value)
+    fn insert_at(
+        &mut self,
+        at: usize,
+        interval: Interval<K>,
+        value: V,
+    ) -> Result<(), (Interval<K>, V)>
+    where
+        K: Ord,
+    {
+        if interval.is_empty()
+            || at > 0 && interval.start <= self.1[at - 1].0.end
+            || at < self.1.len() && self.1[at].0.start <= interval.end
+        {
+            return Err((interval, value));
+        }
+        self.1.insert(at, (interval, value));
+        if at <= self.0 {
+            self.0 += 1;
+        }
+        Ok(())
     }
-    pub fn remove(self) -> V {
-        self.remove_entry().1
+    pub fn insert_before<T: Into<Interval<K>>>(
+        &mut self,
+        key: T,
+        value: V,
+    ) -> Result<(), (Interval<K>, V)>
+    where
+        K: Ord,
+    {
+        self.insert_at(self.0, key.into(), value)
+    }
+    pub fn insert_after<T: Into<Interval<K>>>(
+        &mut self,
+        key: T,
+        value: V,
+    ) -> Result<(), (Interval<K>, V)>
+    where
+        K: Ord,
+    {
+        let at = if self.0 < self.1.len() { self.0 + 1 } else { 0 };
+        self.insert_at(at, key.into(), value)
+    }
+    pub fn split_current(&mut self, at: StartBound<K>) -> Result<(), StartBound<K>>
+    where
+        K: Ord + Clone,
+        V: Clone,
+    {
+        if self.0 < self.1.len() {
+            OccupiedEntry(self.0, &mut *self.1).split_at(at)
+        } else {
+            Err(at)
+        }
+    }
+    pub fn remove_current(&mut self) -> Option<(Interval<K>, V)> {
+        if self.0 < self.1.len() {
+            Some(self.1.remove(self.0))
+        } else {
+            None
+        }
     }
 }
 
@@ -826,6 +2161,17 @@ where
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a, K, V> arbitrary::Arbitrary<'a> for IntervalMap<K, V>
+where
+    K: arbitrary::Arbitrary<'a> + Ord + Clone,
+    V: arbitrary::Arbitrary<'a> + Clone,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary_iter::<(Interval<K>, V)>()?.collect()
+    }
+}
+
 impl<K, Q, V> std::ops::Index<&Q> for IntervalMap<K, V>
 where
     K: Borrow<Q>,
@@ -1018,4 +2364,833 @@ mod test {
         map3.insert(.., 0);
         assert_eq!(map1, map3)
     }
+
+    #[test]
+    fn invert_unbounded() {
+        let mut map = IntervalMap::default();
+        map.insert(..0, 0);
+        map.invert(1);
+        assert_eq!(map, FromIterator::from_iter(vec![(0.., 1)]));
+        map.insert(..=-5, 2);
+        map.invert(3);
+        assert_eq!(
+            map,
+            FromIterator::from_iter(vec![(
+                Interval::new(Bound::Excluded(-5), Bound::Excluded(0)),
+                3
+            )])
+        );
+    }
+
+    #[test]
+    fn invert_unbounded_end() {
+        let mut map = IntervalMap::default();
+        map.insert(255u8.., 64);
+        map.invert(0);
+        assert_eq!(map, FromIterator::from_iter(vec![(..255, 0)]));
+    }
+
+    #[test]
+    fn try_partial_ord() {
+        let mut map = IntervalMap::new();
+        assert_eq!(map.try_insert(0.0..1.0, 1), Ok(()));
+        assert_eq!(map.try_insert(f64::NAN..2.0, 2), Err(IncomparableKey));
+        assert_eq!(map.try_overwrite(0.5..=3.0, 3), Ok(()));
+        assert_eq!(map.try_overwrite(..f64::NAN, 4), Err(IncomparableKey));
+        assert_eq!(map.try_get(&0.25), Ok(Some(&1)));
+        assert_eq!(map.try_get(&3.0), Ok(Some(&3)));
+        assert_eq!(map.try_get(&5.0), Ok(None));
+        assert_eq!(map.try_get(&f64::NAN), Err(IncomparableKey));
+        assert_eq!(map.try_range(0.0..=3.0).map(Iterator::count), Ok(2));
+        assert_eq!(map.try_range(2.0..2.5).map(Iterator::count), Ok(0));
+        assert!(map.try_range(f64::NAN..).is_err());
+    }
+
+    #[cfg(feature = "ordered-float")]
+    #[test]
+    fn ordered_float_keys() {
+        use ordered_float::OrderedFloat;
+        let mut map = IntervalMap::new();
+        map.insert(OrderedFloat(0.0)..OrderedFloat(1.0), 1);
+        map.insert(OrderedFloat(2.0)..OrderedFloat(3.0), 2);
+        assert_eq!(map.get(&OrderedFloat(0.5)), Some(&1));
+        assert_eq!(map.get(&OrderedFloat(f64::NAN)), None);
+        assert_eq!(
+            map.nearest(&OrderedFloat(1.75)).map(|(_, _, v)| v),
+            Some(&2)
+        );
+    }
+
+    #[test]
+    fn split_off_at() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 0);
+        map.insert(Interval::new(Bound::Excluded(10), Bound::Included(20)), 1);
+        let mut left = map.clone();
+        let right = left.split_off_at(StartBound(Bound::Excluded(5)));
+        assert_eq!(left, FromIterator::from_iter(vec![(0..=5, 0)]));
+        assert_eq!(
+            right.inner(),
+            &[
+                (Interval::new(Bound::Excluded(5), Bound::Excluded(10)), 0),
+                (Interval::new(Bound::Excluded(10), Bound::Included(20)), 1),
+            ]
+        );
+        let mut left = map.clone();
+        let right = left.split_off_at(StartBound(Bound::Excluded(10)));
+        assert_eq!(left, FromIterator::from_iter(vec![(0..10, 0)]));
+        assert_eq!(right.inner(), &map.inner()[1..]);
+        let mut left = map.clone();
+        let right = left.split_off(10);
+        assert_eq!(left, FromIterator::from_iter(vec![(0..10, 0)]));
+        assert_eq!(right.inner(), &map.inner()[1..]);
+        let mut left = map.clone();
+        assert_eq!(left.split_off_at(StartBound(Bound::Unbounded)), map);
+        assert!(left.is_empty());
+    }
+
+    #[test]
+    fn split_range() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 0);
+        map.insert(20..30, 1);
+        let (before, within, after) = map.clone().split_range(5..=20);
+        assert_eq!(before, FromIterator::from_iter(vec![(0..5, 0)]));
+        assert_eq!(
+            within,
+            FromIterator::from_iter(vec![
+                (Interval::from(5..10), 0),
+                (Interval::from(20..=20), 1)
+            ])
+        );
+        assert_eq!(
+            after.inner(),
+            &[(Interval::new(Bound::Excluded(20), Bound::Excluded(30)), 1)]
+        );
+        let (before, within, after) = map.clone().split_range(..);
+        assert!(before.is_empty() && after.is_empty());
+        assert_eq!(within, map);
+    }
+
+    #[test]
+    fn insert_exclusive() {
+        let mut map = IntervalMap::new();
+        assert_eq!(map.try_insert_exclusive(10..20, 1), Ok(()));
+        assert_eq!(map.try_insert_exclusive(30..40, 2), Ok(()));
+        assert_eq!(map.try_insert_exclusive(20..30, 3), Ok(()));
+        assert_eq!(
+            map.try_insert_exclusive(15..=30, 4),
+            Err(OverlapError {
+                interval: (15..=30).into(),
+                value: 4,
+                conflicts: vec![
+                    ((10..20).into(), 1),
+                    ((20..30).into(), 3),
+                    ((30..40).into(), 2)
+                ],
+            })
+        );
+        assert_eq!(map.len(), 3);
+        assert!(map.is_vacant(..10));
+        assert!(map.is_vacant(40..));
+        assert!(!map.is_vacant(..=10));
+        assert_eq!(
+            map.conflicts(35..50).collect::<Vec<_>>(),
+            vec![(&(30..40).into(), &2)]
+        );
+        assert_eq!(map.conflicts(0..10).count(), 0);
+    }
+
+    #[test]
+    fn coverage() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 1);
+        map.insert(10..20, 2);
+        map.insert(30..40, 3);
+        assert!(map.covers(5..15));
+        assert!(!map.covers(5..=20));
+        assert!(map.covers(Interval::new(Bound::Excluded(20), Bound::Excluded(20))));
+        assert!(map.covers(5..5));
+        let reversed = Interval::new(Bound::Included(20), Bound::Excluded(10));
+        assert!(map.covers(reversed));
+        assert!(map.conflicts(reversed).next().is_none());
+        assert_eq!(
+            map.coverage(5..35),
+            Coverage {
+                covered: vec![(5..10).into(), (10..20).into(), (30..35).into()],
+                uncovered: vec![(20..30).into()],
+            }
+        );
+        assert_eq!(
+            map.coverage(..),
+            Coverage {
+                covered: vec![(0..10).into(), (10..20).into(), (30..40).into()],
+                uncovered: vec![(..0).into(), (20..30).into(), (40..).into()],
+            }
+        );
+        assert_eq!(map.get_interval(12..=19), Some(&2));
+        assert_eq!(map.get_interval(5..15), None);
+        assert_eq!(map.get_interval(35..45), None);
+    }
+
+    #[test]
+    fn fill_gaps() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 1);
+        map.insert(20..30, 2);
+        map.insert(40..50, 3);
+        let complement = map.complement_map(|_, prev, next| (prev.copied(), next.copied()));
+        assert_eq!(
+            complement,
+            FromIterator::from_iter(vec![
+                (Interval::from(..0), (None, Some(1))),
+                (Interval::from(10..20), (Some(1), Some(2))),
+                (Interval::from(30..40), (Some(2), Some(3))),
+                (Interval::from(50..), (Some(3), None)),
+            ])
+        );
+        map.fill_gaps(5..45, |gap, prev, next| match (prev, next) {
+            (Some(p), Some(n)) if gap.start != 30 => Some(p * 10 + n),
+            _ => None,
+        });
+        assert_eq!(
+            map,
+            FromIterator::from_iter(vec![(0..10, 1), (10..20, 12), (20..30, 2), (40..50, 3),])
+        );
+        map.fill_gaps(.., |_, _, _| Some(0));
+        assert!(map.covers(..));
+        assert_eq!(map.len(), 7);
+    }
+
+    #[test]
+    fn forward_backward_fill() {
+        let map: IntervalMap<i32, _> = FromIterator::from_iter(vec![
+            (Interval::from(0..10), 1),
+            (Interval::from(12..20), 2),
+            (Interval::from(30..=40), 3),
+            (Interval::new(Bound::Excluded(45), Bound::Unbounded), 4),
+        ]);
+        let mut forward = map.clone();
+        forward.forward_fill(5);
+        assert_eq!(
+            forward.inner(),
+            &[
+                ((0..12).into(), 1),
+                ((12..20).into(), 2),
+                ((30..=45).into(), 3),
+                (Interval::new(Bound::Excluded(45), Bound::Unbounded), 4),
+            ]
+        );
+        let mut backward = map.clone();
+        backward.backward_fill(10);
+        assert_eq!(
+            backward.inner(),
+            &[
+                ((0..10).into(), 1),
+                ((10..20).into(), 2),
+                ((20..=40).into(), 3),
+                (Interval::new(Bound::Excluded(40), Bound::Unbounded), 4),
+            ]
+        );
+        let mut nearest = map;
+        nearest.fill_to_nearest(10);
+        assert_eq!(
+            nearest.inner(),
+            &[
+                ((0..11).into(), 1),
+                ((11..25).into(), 2),
+                ((25..42).into(), 3),
+                (Interval::new(Bound::Included(42), Bound::Unbounded), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn slivers() {
+        let map: IntervalMap<i32, _> = FromIterator::from_iter(vec![
+            (0..10, 1),
+            (10..11, 2),
+            (11..15, 3),
+            (20..21, 4),
+            (21..40, 5),
+        ]);
+        let mut removed = map.clone();
+        removed.remove_shorter_than(2);
+        assert_eq!(
+            removed,
+            FromIterator::from_iter(vec![(0..10, 1), (11..15, 3), (21..40, 5)])
+        );
+        let mut previous = map.clone();
+        previous.absorb_slivers(2, SliverPolicy::Previous);
+        assert_eq!(
+            previous,
+            FromIterator::from_iter(vec![(0..11, 1), (11..15, 3), (20..21, 4), (21..40, 5)])
+        );
+        let mut next = map.clone();
+        next.absorb_slivers(2, SliverPolicy::Next);
+        assert_eq!(
+            next,
+            FromIterator::from_iter(vec![(0..10, 1), (10..15, 3), (20..40, 5)])
+        );
+        let mut larger = map;
+        larger.absorb_slivers(5, SliverPolicy::Larger);
+        assert_eq!(
+            larger,
+            FromIterator::from_iter(vec![(0..15, 1), (20..40, 5)])
+        );
+        let map: IntervalMap<i32, _> =
+            FromIterator::from_iter(vec![(0..5, 1), (5..6, 2), (6..7, 3), (7..100, 4)]);
+        let mut previous = map.clone();
+        previous.absorb_slivers(3, SliverPolicy::Previous);
+        assert_eq!(
+            previous,
+            FromIterator::from_iter(vec![(0..7, 1), (7..100, 4)])
+        );
+        let mut larger = map;
+        larger.absorb_slivers(8, SliverPolicy::Larger);
+        assert_eq!(larger, FromIterator::from_iter(vec![(0..100, 4)]));
+    }
+
+    #[test]
+    fn snap_to_grid() {
+        let mut map = IntervalMap::new();
+        map.try_insert(0.0..10.2, 1).unwrap();
+        map.try_insert(10.2..10.4, 2).unwrap();
+        map.try_insert(10.4..=19.9, 3).unwrap();
+        map.try_insert(Interval::new(Bound::Excluded(19.9), Bound::Unbounded), 4)
+            .unwrap();
+        map.snap_to_grid(&0.5);
+        assert_eq!(
+            map.inner(),
+            &[
+                ((0.0..10.0).into(), 1),
+                ((10.0..10.5).into(), 2),
+                ((10.5..=20.0).into(), 3),
+                (Interval::new(Bound::Excluded(20.0), Bound::Unbounded), 4),
+            ]
+        );
+        let mut map = IntervalMap::new();
+        map.insert(0..=11, 1);
+        map.insert(12..20, 2);
+        map.snap_to_grid(&5);
+        assert_eq!(
+            map,
+            FromIterator::from_iter(vec![
+                (Interval::from(0..=10), 1),
+                (Interval::new(Bound::Excluded(10), Bound::Excluded(20)), 2)
+            ])
+        );
+    }
+
+    #[test]
+    fn shift_from() {
+        let map: IntervalMap<usize, _> =
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..6, 'b'), (6..9, 'c')]);
+        let mut forward = map.clone();
+        forward.shift_from(&5, Delta::Forward(10));
+        assert_eq!(
+            forward,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..16, 'b'), (16..19, 'c')])
+        );
+        let mut backward = map;
+        backward.shift_from(&6, Delta::Backward(4));
+        assert_eq!(
+            backward,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..5, 'c')]),
+            "b collapses to [3, 2) and is dropped; c is clipped by a"
+        );
+    }
+
+    #[test]
+    fn splice_domain() {
+        let map: IntervalMap<usize, _> =
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..6, 'b'), (6..9, 'c')]);
+        let mut previous = map.clone();
+        previous.splice_domain(3, 0, 2, SplicePolicy::Previous);
+        assert_eq!(
+            previous,
+            FromIterator::from_iter(vec![(0..5, 'a'), (5..8, 'b'), (8..11, 'c')])
+        );
+        let mut next = map.clone();
+        next.splice_domain(3, 0, 2, SplicePolicy::Next);
+        assert_eq!(
+            next,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..8, 'b'), (8..11, 'c')])
+        );
+        let mut neither = map.clone();
+        neither.splice_domain(3, 0, 2, SplicePolicy::Neither);
+        assert_eq!(
+            neither,
+            FromIterator::from_iter(vec![(0..3, 'a'), (5..8, 'b'), (8..11, 'c')])
+        );
+        let mut removed = map.clone();
+        removed.splice_domain(2, 5, 0, SplicePolicy::Neither);
+        assert_eq!(
+            removed,
+            FromIterator::from_iter(vec![(0..2, 'a'), (2..4, 'c')])
+        );
+        let mut deleted = map.clone();
+        deleted.splice_domain(4, 1, 0, SplicePolicy::Neither);
+        assert_eq!(
+            deleted,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..5, 'b'), (5..8, 'c')])
+        );
+        let mut inserted = map.clone();
+        inserted.splice_domain(4, 0, 3, SplicePolicy::Neither);
+        assert_eq!(
+            inserted,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..9, 'b'), (9..12, 'c')])
+        );
+        let mut replaced = map.clone();
+        replaced.splice_domain(4, 1, 3, SplicePolicy::Previous);
+        assert_eq!(
+            replaced,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..8, 'b'), (8..11, 'c')])
+        );
+        let mut replaced = map;
+        replaced.splice_domain(4, 1, 3, SplicePolicy::Neither);
+        assert_eq!(
+            replaced,
+            FromIterator::from_iter(vec![(0..3, 'a'), (3..8, 'b'), (8..11, 'c')])
+        );
+    }
+
+    #[test]
+    fn map_keys_and_values() {
+        let map: IntervalMap<u32, _> = FromIterator::from_iter(vec![
+            (Interval::from(0..10), 1),
+            (Interval::from(10..=20), 2),
+            (Interval::from(30..), 3),
+        ]);
+        let millis = map
+            .clone()
+            .map_keys_monotone(|s| s as u64 * 1000, Monotonicity::Increasing);
+        assert_eq!(
+            millis,
+            FromIterator::from_iter(vec![
+                (Interval::from(0..10000), 1),
+                (Interval::from(10000..=20000), 2),
+                (Interval::from(30000..), 3),
+            ])
+        );
+        let negated = map
+            .clone()
+            .map_keys_monotone(|s| -(s as i64), Monotonicity::Decreasing);
+        assert_eq!(
+            negated.inner(),
+            &[
+                ((..=-30).into(), 3),
+                ((-20..=-10).into(), 2),
+                (Interval::new(Bound::Excluded(-10), Bound::Included(0)), 1),
+            ]
+        );
+        let collapsed = map
+            .clone()
+            .map_keys_monotone(|s| s / 20, Monotonicity::Increasing);
+        assert_eq!(
+            collapsed,
+            FromIterator::from_iter(vec![(Interval::from(0..=1), 2), (Interval::from(1..), 3)]),
+            "0..10 collapses to [0, 0) and its value 1 is dropped"
+        );
+        assert_eq!(
+            map.clone().map_values(|v| v * 10),
+            FromIterator::from_iter(vec![
+                (Interval::from(0..10), 10),
+                (Interval::from(10..=20), 20),
+                (Interval::from(30..), 30),
+            ])
+        );
+        assert_eq!(
+            map.clone()
+                .try_map_values(|v| if v < 3 { Ok(v) } else { Err(v) }),
+            Err(3)
+        );
+        assert_eq!(
+            map.filter_map_values(|v| if v % 2 == 1 { Some(v) } else { None }),
+            FromIterator::from_iter(vec![(Interval::from(0..10), 1), (Interval::from(30..), 3)])
+        );
+    }
+
+    #[test]
+    fn vacant_entry_gap() {
+        let mut map = IntervalMap::default();
+        map.insert(10..20, 100);
+        map.insert(30..40, 200);
+        match map.entry(5) {
+            Entry::Vacant(v) => assert_eq!(v.gap(), (..10).into()),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        match map.entry(25) {
+            Entry::Vacant(v) => {
+                assert_eq!(v.gap(), (20..30).into());
+                assert_eq!(v.insert_within(15..28, 300), Err(((15..28).into(), 300)));
+            }
+            Entry::Occupied(_) => unreachable!(),
+        }
+        match map.entry(25) {
+            Entry::Vacant(v) => assert_eq!(v.insert_within(22..28, 300), Ok(&mut 300)),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        match map.entry(50) {
+            Entry::Vacant(v) => assert_eq!(*v.insert_gap(400), 400),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(
+            map,
+            FromIterator::from_iter(vec![
+                (Interval::from(10..20), 100),
+                (Interval::from(22..28), 300),
+                (Interval::from(30..40), 200),
+                (Interval::from(40..), 400),
+            ])
+        );
+    }
+
+    #[test]
+    fn occupied_entry_adjust() {
+        use Bound::*;
+        let mut map = IntervalMap::default();
+        map.insert(10..20, 100);
+        map.insert(30..40, 200);
+        map.insert(50..60, 300);
+        match map.entry(35) {
+            Entry::Occupied(mut o) => {
+                assert_eq!(
+                    o.neighbors(),
+                    [
+                        Some((&(10..20).into(), &100)),
+                        Some((&(50..60).into(), &300))
+                    ]
+                );
+                assert_eq!(o.extend_to(15..45), Err((15..45).into()));
+                assert_eq!(o.extend_to(20..50), Ok(()));
+                assert_eq!(o.shrink_to(10..45), Err((10..45).into()));
+                assert_eq!(o.shrink_to(25..45), Ok(()));
+                assert_eq!(
+                    o.split_at(StartBound(Included(25))),
+                    Err(StartBound(Included(25)))
+                );
+                assert_eq!(o.split_at(StartBound(Excluded(35))), Ok(()));
+                assert_eq!(o.key(), &Interval::new(Included(25), Included(35)));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry(12) {
+            Entry::Occupied(o) => o.remove_part(13..15),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(map, unsafe {
+            IntervalMap::from_inner_unchecked(vec![
+                ((10..13).into(), 100),
+                ((15..20).into(), 100),
+                (Interval::new(Included(25), Included(35)), 200),
+                (Interval::new(Excluded(35), Excluded(45)), 200),
+                ((50..60).into(), 300),
+            ])
+        });
+    }
+
+    #[test]
+    fn neighbour_lookup() {
+        let mut map = IntervalMap::default();
+        map.insert(10..20, 100);
+        map.insert(30..40, 200);
+        map.insert(50..60, 300);
+        let i1 = Interval::from(10..20);
+        let i2 = Interval::from(30..40);
+        let i3 = Interval::from(50..60);
+        assert_eq!(map.floor(&5), None);
+        assert_eq!(map.floor(&15), Some((0, &i1, &100)));
+        assert_eq!(map.floor(&25), Some((0, &i1, &100)));
+        assert_eq!(map.ceiling(&25), Some((1, &i2, &200)));
+        assert_eq!(map.ceiling(&35), Some((1, &i2, &200)));
+        assert_eq!(map.ceiling(&65), None);
+        assert_eq!(map.prev_entry(&35), Some((0, &i1, &100)));
+        assert_eq!(map.prev_entry(&15), None);
+        assert_eq!(map.next_entry(&35), Some((2, &i3, &300)));
+        assert_eq!(map.next_entry(&45), Some((2, &i3, &300)));
+        assert_eq!(map.next_entry(&55), None);
+        assert_eq!(map.nearest(&5), Some((0, &i1, &100)));
+        assert_eq!(map.nearest(&23), Some((0, &i1, &100)));
+        assert_eq!(map.nearest(&27), Some((1, &i2, &200)));
+        assert_eq!(map.nearest(&35), Some((1, &i2, &200)));
+        assert_eq!(map.nearest(&100), Some((2, &i3, &300)));
+        assert_eq!(IntervalMap::<i32, i32>::new().nearest(&0), None);
+    }
+
+    #[test]
+    fn cursor() {
+        use Bound::*;
+        let mut map = IntervalMap::default();
+        map.insert(10..20, 100);
+        map.insert(30..40, 200);
+        let mut cursor = map.cursor_at(&25);
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.peek_prev(), Some((&(10..20).into(), &100)));
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some((&(10..20).into(), &100)));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some((&(30..40).into(), &200)));
+
+        let mut cursor = map.cursor_front_mut();
+        assert_eq!(cursor.insert_before(0..11, 0), Err(((0..11).into(), 0)));
+        assert_eq!(cursor.insert_before(0..10, 0), Ok(()));
+        assert_eq!(cursor.insert_after(15..25, 0), Err(((15..25).into(), 0)));
+        assert_eq!(cursor.insert_after(20..30, 0), Ok(()));
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.split_current(StartBound(Included(15))), Ok(()));
+        *cursor.current_mut().unwrap().1 += 1;
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(((15..20).into(), 100)));
+        assert_eq!(cursor.current(), Some((&(20..30).into(), &0)));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.insert_before(40.., 300), Ok(()));
+        assert_eq!(
+            map,
+            FromIterator::from_iter(vec![
+                (Interval::from(0..10), 0),
+                (Interval::from(10..15), 101),
+                (Interval::from(20..30), 0),
+                (Interval::from(30..40), 200),
+                (Interval::from(40..), 300),
+            ])
+        );
+    }
+
+    #[test]
+    fn from_iter_with_policy() {
+        use Bound::*;
+        let input = vec![(0..10, 1), (5..15, 2), (20..30, 3), (12..25, 4)];
+        assert_eq!(
+            IntervalMap::from_iter_with_policy(input.clone(), ConflictPolicy::FirstWins),
+            Ok(FromIterator::from_iter(input.clone()))
+        );
+        let mut map = IntervalMap::new();
+        for (i, v) in input.clone() {
+            map.overwrite(i, v);
+        }
+        assert_eq!(
+            IntervalMap::from_iter_with_policy(input.clone(), ConflictPolicy::LastWins),
+            Ok(map)
+        );
+        assert_eq!(
+            IntervalMap::from_iter_with_policy(
+                input.clone(),
+                ConflictPolicy::Combine(|x, y| x + y)
+            ),
+            Ok(FromIterator::from_iter(vec![
+                (0..5, 1),
+                (5..10, 3),
+                (10..12, 2),
+                (12..15, 6),
+                (15..20, 4),
+                (20..25, 7),
+                (25..30, 3),
+            ]))
+        );
+        assert_eq!(
+            IntervalMap::from_iter_with_policy(input, ConflictPolicy::Error),
+            Err(vec![
+                Conflict {
+                    interval: (5..10).into(),
+                    sources: vec![0, 1]
+                },
+                Conflict {
+                    interval: (12..15).into(),
+                    sources: vec![1, 3]
+                },
+                Conflict {
+                    interval: (20..25).into(),
+                    sources: vec![2, 3]
+                },
+            ])
+        );
+        assert_eq!(
+            IntervalMap::from_iter_with_policy(
+                vec![(5..5, 9), (0..10, 1), (5..15, 2)],
+                ConflictPolicy::Error
+            ),
+            Err(vec![Conflict {
+                interval: (5..10).into(),
+                sources: vec![1, 2]
+            }])
+        );
+        assert_eq!(
+            IntervalMap::from_iter_with_policy(
+                vec![
+                    (Interval::new(Unbounded, Included(5)), 1),
+                    (Interval::new(Excluded(5), Unbounded), 2),
+                    (Interval::new(Included(5), Included(5)), 3),
+                ],
+                ConflictPolicy::FirstWins
+            ),
+            Ok(unsafe {
+                IntervalMap::from_inner_unchecked(vec![
+                    (Interval::new(Unbounded, Included(5)), 1),
+                    (Interval::new(Excluded(5), Unbounded), 2),
+                ])
+            })
+        );
+    }
+
+    #[test]
+    fn returning() {
+        use Bound::*;
+        let mut map = IntervalMap::default();
+        map.insert(10..20, 100);
+        map.insert(30..40, 200);
+        assert_eq!(
+            map.insert_returning(15..=50, 300),
+            vec![(20..30).into(), Interval::new(Included(40), Included(50))]
+        );
+        assert_eq!(map.insert_returning(15..50, 400), vec![]);
+        assert_eq!(
+            map.overwrite_returning(35..45, 500),
+            vec![((35..40).into(), 200), ((40..45).into(), 300)]
+        );
+        assert_eq!(map.remove_returning(..12), vec![((10..12).into(), 100)]);
+        assert_eq!(map, unsafe {
+            IntervalMap::from_inner_unchecked(vec![
+                ((12..20).into(), 100),
+                ((20..30).into(), 300),
+                ((30..35).into(), 200),
+                ((35..45).into(), 500),
+                (Interval::new(Included(45), Included(50)), 300),
+            ])
+        });
+    }
+
+    #[test]
+    fn diff() {
+        let mut map1 = IntervalMap::default();
+        map1.insert(0..10, 1);
+        map1.insert(10..20, 2);
+        map1.insert(30..40, 3);
+        map1.insert(50..60, 4);
+        let mut map2 = IntervalMap::default();
+        map2.insert(0..5, 1);
+        map2.insert(5..20, 5);
+        map2.insert(25..35, 3);
+        map2.insert(35..45, 6);
+        use DiffSegment::*;
+        let diff: Vec<_> = map1.diff(&map2).collect();
+        assert_eq!(
+            diff,
+            vec![
+                Changed {
+                    interval: (5..10).into(),
+                    old: &1,
+                    new: &5
+                },
+                Changed {
+                    interval: (10..20).into(),
+                    old: &2,
+                    new: &5
+                },
+                Added((25..30).into(), &3),
+                Changed {
+                    interval: (35..40).into(),
+                    old: &3,
+                    new: &6
+                },
+                Added((40..45).into(), &6),
+                Removed((50..60).into(), &4),
+            ]
+        );
+        assert_eq!(map2.diff(&map2).next(), None);
+        let map4 = FromIterator::from_iter(vec![(0..5, 7), (5..10, 7)]);
+        assert_eq!(
+            IntervalMap::new().diff(&map4).collect::<Vec<_>>(),
+            vec![Added((0..10).into(), &7)]
+        );
+        let mut map3 = map1.clone();
+        map3.apply_diff(diff);
+        assert_eq!(
+            map3,
+            FromIterator::from_iter(vec![
+                (0..5, 1),
+                (5..10, 5),
+                (10..20, 5),
+                (25..30, 3),
+                (30..35, 3),
+                (35..40, 6),
+                (40..45, 6),
+            ])
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Amount(i32);
+
+    impl SplitValue<i32> for Amount {
+        fn split(self, at: &StartBound<i32>, whole: &Interval<i32>) -> (Self, Self) {
+            let point = |b: &StartBound<i32>| match b.0 {
+                Bound::Included(x) => x,
+                Bound::Excluded(x) => x + 1,
+                Bound::Unbounded => unreachable!(),
+            };
+            let (s, e) = (point(&whole.start), point(&whole.end.into()));
+            let left = self.0 * (point(at) - s) / (e - s);
+            (Amount(left), Amount(self.0 - left))
+        }
+    }
+
+    #[test]
+    fn splitting() {
+        let mut map = IntervalMap::new();
+        map.insert_splitting(0..10, Amount(100));
+        map.insert_splitting(5..15, Amount(50));
+        map.overwrite_splitting(8..12, Amount(7));
+        assert_eq!(
+            map.inner(),
+            &[
+                (Interval::from(0..8), Amount(80)),
+                (Interval::from(8..12), Amount(7)),
+                (Interval::from(12..15), Amount(15)),
+            ]
+        );
+        map.remove_splitting(2..4);
+        let right = map.split_off_splitting(6);
+        assert_eq!(
+            map.inner(),
+            &[
+                (Interval::from(0..2), Amount(20)),
+                (Interval::from(4..6), Amount(20)),
+            ]
+        );
+        assert_eq!(
+            right.inner(),
+            &[
+                (Interval::from(6..8), Amount(20)),
+                (Interval::from(8..12), Amount(7)),
+                (Interval::from(12..15), Amount(15)),
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_with() {
+        let mut map = IntervalMap::new();
+        map.insert_with(0..10, 1u32, |x, y| *x += y);
+        map.insert_with(5..15, 2, |x, y| *x += y);
+        map.insert_with(20..30, 4, |x, y| *x += y);
+        map.insert_with(..=25, 8, |x, y| *x += y);
+        assert_eq!(
+            map,
+            IntervalMap::from_iter(vec![
+                (Interval::from(..0), 8),
+                (Interval::from(0..5), 9),
+                (Interval::from(5..10), 11),
+                (Interval::from(10..15), 10),
+                (Interval::from(15..20), 8),
+                (Interval::from(20..=25), 12),
+                (Interval::new(Bound::Excluded(25), Bound::Excluded(30)), 4),
+            ])
+        );
+        assert_eq!(map.get(&-100), Some(&8));
+        assert_eq!(map.get(&30), None);
+    }
 }
@@ -0,0 +1,260 @@
+use std::{collections::VecDeque, ops::Deref, ops::Range};
+
+use super::interval::Interval;
+use super::interval_map::IntervalMap;
+
+#[derive(Clone, Debug)]
+struct Change<K, V> {
+    index: usize,
+    len: usize,
+    entries: Vec<(Interval<K>, V)>,
+}
+
+impl<K, V> Change<K, V> {
+    fn swap(&mut self, map: &mut IntervalMap<K, V>) {
+        let entries = std::mem::take(&mut self.entries);
+        let range = self.index..self.index + self.len;
+        self.len = entries.len();
+        self.entries = map.splice(range, entries);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct JournaledIntervalMap<K, V> {
+    map: IntervalMap<K, V>,
+    pending: Vec<Change<K, V>>,
+    undo: VecDeque<Vec<Change<K, V>>>,
+    redo: Vec<Vec<Change<K, V>>>,
+    limit: usize,
+}
+
+impl<K, V> JournaledIntervalMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_history_limit(usize::MAX)
+    }
+    pub fn with_history_limit(limit: usize) -> Self {
+        Self {
+            map: IntervalMap::new(),
+            pending: vec![],
+            undo: VecDeque::new(),
+            redo: vec![],
+            limit,
+        }
+    }
+    pub fn history_limit(&self) -> usize {
+        self.limit
+    }
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.pending.is_empty() || !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    pub fn checkpoint(&mut self) {
+        if !self.pending.is_empty() {
+            let changes = std::mem::take(&mut self.pending);
+            self.undo.push_back(changes);
+            self.trim();
+        }
+    }
+    pub fn undo(&mut self) -> bool {
+        self.checkpoint();
+        match self.undo.pop_back() {
+            Some(mut changes) => {
+                for change in changes.iter_mut().rev() {
+                    change.swap(&mut self.map);
+                }
+                self.redo.push(changes);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn redo(&mut self) -> bool {
+        self.checkpoint();
+        match self.redo.pop() {
+            Some(mut changes) => {
+                for change in changes.iter_mut() {
+                    change.swap(&mut self.map);
+                }
+                self.undo.push_back(changes);
+                self.trim();
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn clear_history(&mut self) {
+        self.pending.clear();
+        self.undo.clear();
+        self.redo.clear();
+    }
+    pub fn into_inner(self) -> IntervalMap<K, V> {
+        self.map
+    }
+    fn trim(&mut self) {
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
+
+impl<K, V> JournaledIntervalMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone + PartialEq,
+{
+    fn record<F>(&mut self, range: Range<usize>, f: F)
+    where
+        F: FnOnce(&mut IntervalMap<K, V>),
+    {
+        let entries = self.map.inner()[range.clone()].to_vec();
+        let len = self.map.len();
+        f(&mut self.map);
+        let len = range.end + self.map.len() - len - range.start;
+        if self.map.inner()[range.start..range.start + len] != entries[..] {
+            self.pending.push(Change {
+                index: range.start,
+                len,
+                entries,
+            });
+            self.redo.clear();
+        }
+    }
+    pub fn insert<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let range = self.map.overlapping(&interval);
+        self.record(range, |map| map.insert(interval, val))
+    }
+    pub fn overwrite<T: Into<Interval<K>>>(&mut self, key: T, val: V) {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let range = self.map.overlapping(&interval);
+        self.record(range, |map| map.overwrite(interval, val))
+    }
+    pub fn remove<T: Into<Interval<K>>>(&mut self, key: T) {
+        let interval = key.into();
+        if interval.is_empty() {
+            return;
+        }
+        let range = self.map.overlapping(&interval);
+        self.record(range, |map| map.remove(interval))
+    }
+    pub fn append(&mut self, other: &mut IntervalMap<K, V>) {
+        let hull = match (other.inner().first(), other.inner().last()) {
+            (Some((first, _)), Some((last, _))) => Interval {
+                start: first.start.clone(),
+                end: last.end.clone(),
+            },
+            _ => return,
+        };
+        let range = self.map.overlapping(&hull);
+        self.record(range, |map| map.append(other))
+    }
+    pub fn invert(&mut self, val: V) {
+        let range = 0..self.map.len();
+        self.record(range, |map| map.invert(val))
+    }
+}
+
+impl<K, V> Default for JournaledIntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Deref for JournaledIntervalMap<K, V> {
+    type Target = IntervalMap<K, V>;
+    fn deref(&self) -> &IntervalMap<K, V> {
+        &self.map
+    }
+}
+
+impl<K, V> From<IntervalMap<K, V>> for JournaledIntervalMap<K, V> {
+    fn from(map: IntervalMap<K, V>) -> Self {
+        Self { map, ..Self::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::FromIterator;
+
+    #[test]
+    fn undo_redo() {
+        let mut map = JournaledIntervalMap::new();
+        let mut snapshots = vec![map.clone().into_inner()];
+        map.insert(10..20, 100);
+        map.insert(30..40, 200);
+        map.checkpoint();
+        snapshots.push(map.clone().into_inner());
+        map.overwrite(15..35, 300);
+        map.checkpoint();
+        snapshots.push(map.clone().into_inner());
+        map.remove(..12);
+        map.insert(0..50, 400);
+        map.checkpoint();
+        snapshots.push(map.clone().into_inner());
+        let mut other = IntervalMap::new();
+        other.insert(45..55, 500);
+        other.insert(60..70, 600);
+        map.append(&mut other);
+        map.checkpoint();
+        snapshots.push(map.clone().into_inner());
+        map.invert(700);
+        snapshots.push(map.clone().into_inner());
+        for snapshot in snapshots.iter().rev().skip(1) {
+            assert!(map.undo());
+            assert_eq!(&*map, snapshot);
+        }
+        assert!(!map.undo());
+        for snapshot in snapshots.iter().skip(1) {
+            assert!(map.redo());
+            assert_eq!(&*map, snapshot);
+        }
+        assert!(!map.redo());
+        map.undo();
+        map.remove(0..5);
+        assert!(!map.can_redo());
+    }
+
+    #[test]
+    fn no_op() {
+        let mut map = JournaledIntervalMap::new();
+        map.insert(10..20, 1);
+        map.checkpoint();
+        map.insert(10..20, 2);
+        map.overwrite(10..20, 1);
+        map.remove(30..40);
+        map.checkpoint();
+        assert!(map.undo());
+        assert!(map.is_empty());
+        assert!(!map.undo());
+    }
+
+    #[test]
+    fn history_limit() {
+        let mut map = JournaledIntervalMap::with_history_limit(2);
+        for i in 0..5 {
+            map.insert(i * 10..i * 10 + 5, i);
+            map.checkpoint();
+        }
+        assert!(map.undo());
+        assert!(map.undo());
+        assert!(!map.undo());
+        assert_eq!(
+            *map,
+            FromIterator::from_iter(vec![(0..5, 0), (10..15, 1), (20..25, 2)])
+        );
+    }
+}
@@ -1,8 +1,22 @@
 #![feature(decl_macro)]
 
 pub mod bound;
+#[cfg(feature = "concurrent")]
+pub mod concurrent_interval_map;
+pub mod depth_map;
 pub mod interval;
 pub mod interval_map;
+pub mod journaled_interval_map;
+pub mod measure;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod persistent_interval_map;
 
+#[cfg(feature = "concurrent")]
+pub use self::concurrent_interval_map::ConcurrentIntervalMap;
+pub use self::depth_map::DepthMap;
 pub use self::interval::Interval;
-pub use self::interval_map::IntervalMap;
+pub use self::interval_map::{IntervalMap, SplitValue};
+pub use self::journaled_interval_map::JournaledIntervalMap;
+pub use self::measure::{Delta, Measure, Midpoint, Snap, Translate};
+pub use self::persistent_interval_map::PersistentIntervalMap;
@@ -0,0 +1,258 @@
+pub trait Measure {
+    type Distance: PartialOrd;
+    fn distance(&self, other: &Self) -> Self::Distance;
+}
+
+pub trait Midpoint: Measure {
+    fn midpoint(&self, other: &Self) -> Self;
+}
+
+pub trait Snap {
+    fn snap(&self, step: &Self) -> Self;
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Delta<D> {
+    Forward(D),
+    Backward(D),
+}
+
+impl<D> Delta<D> {
+    pub fn reverse(self) -> Self {
+        match self {
+            Delta::Forward(d) => Delta::Backward(d),
+            Delta::Backward(d) => Delta::Forward(d),
+        }
+    }
+}
+
+pub trait Translate: Measure {
+    fn translate(&self, delta: &Delta<Self::Distance>) -> Self;
+}
+
+#[doc(hidden)]
+macro __impl_int($($t:ty => $u:ty),*) {
+    $(
+        impl Measure for $t {
+            type Distance = $u;
+            fn distance(&self, other: &Self) -> $u {
+                if self < other {
+                    other.wrapping_sub(*self) as $u
+                } else {
+                    self.wrapping_sub(*other) as $u
+                }
+            }
+        }
+
+        impl Midpoint for $t {
+            fn midpoint(&self, other: &Self) -> $t {
+                let half = (self.distance(other) / 2) as $t;
+                if self < other {
+                    self.wrapping_add(half)
+                } else {
+                    other.wrapping_add(half)
+                }
+            }
+        }
+
+        impl Translate for $t {
+            fn translate(&self, delta: &Delta<$u>) -> $t {
+                match *delta {
+                    Delta::Forward(d) if d > self.distance(&<$t>::MAX) => <$t>::MAX,
+                    Delta::Forward(d) => self.wrapping_add(d as $t),
+                    Delta::Backward(d) if d > self.distance(&<$t>::MIN) => <$t>::MIN,
+                    Delta::Backward(d) => self.wrapping_sub(d as $t),
+                }
+            }
+        }
+
+        impl Snap for $t {
+            fn snap(&self, step: &Self) -> $t {
+                if step.distance(&0) <= 1 {
+                    return *self;
+                }
+                let r = self.rem_euclid(*step);
+                let up = step.distance(&0) - r as $u;
+                let round_up = r as $u >= up;
+                let (down, up) = (self.checked_sub(r), self.checked_add(up as $t));
+                if round_up {
+                    up.or(down)
+                } else {
+                    down.or(up)
+                }
+                .unwrap_or(*self)
+            }
+        }
+    )*
+}
+
+#[doc(hidden)]
+macro __impl_float($($t:ty),*) {
+    $(
+        impl Measure for $t {
+            type Distance = $t;
+            fn distance(&self, other: &Self) -> $t {
+                (self - other).abs()
+            }
+        }
+
+        impl Midpoint for $t {
+            fn midpoint(&self, other: &Self) -> $t {
+                self / 2.0 + other / 2.0
+            }
+        }
+
+        impl Translate for $t {
+            fn translate(&self, delta: &Delta<$t>) -> $t {
+                match *delta {
+                    Delta::Forward(d) => self + d,
+                    Delta::Backward(d) => self - d,
+                }
+            }
+        }
+
+        impl Snap for $t {
+            fn snap(&self, step: &Self) -> $t {
+                if *step == 0.0 {
+                    return *self;
+                }
+                (self / step).round() * step
+            }
+        }
+    )*
+}
+
+__impl_int!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize
+);
+__impl_float!(f32, f64);
+
+#[cfg(feature = "ordered-float")]
+#[doc(hidden)]
+macro __impl_ordered_float($($t:ty),*) {
+    $(
+        impl Measure for ordered_float::OrderedFloat<$t> {
+            type Distance = $t;
+            fn distance(&self, other: &Self) -> $t {
+                (self.0 - other.0).abs()
+            }
+        }
+
+        impl Midpoint for ordered_float::OrderedFloat<$t> {
+            fn midpoint(&self, other: &Self) -> Self {
+                ordered_float::OrderedFloat(Midpoint::midpoint(&self.0, &other.0))
+            }
+        }
+
+        impl Translate for ordered_float::OrderedFloat<$t> {
+            fn translate(&self, delta: &Delta<$t>) -> Self {
+                ordered_float::OrderedFloat(self.0.translate(delta))
+            }
+        }
+
+        impl Snap for ordered_float::OrderedFloat<$t> {
+            fn snap(&self, step: &Self) -> Self {
+                ordered_float::OrderedFloat(self.0.snap(&step.0))
+            }
+        }
+
+        impl Measure for ordered_float::NotNan<$t> {
+            type Distance = $t;
+            fn distance(&self, other: &Self) -> $t {
+                (self.into_inner() - other.into_inner()).abs()
+            }
+        }
+
+        impl Midpoint for ordered_float::NotNan<$t> {
+            fn midpoint(&self, other: &Self) -> Self {
+                let midpoint = Midpoint::midpoint(&self.into_inner(), &other.into_inner());
+                ordered_float::NotNan::new(midpoint).unwrap_or(*self)
+            }
+        }
+
+        impl Translate for ordered_float::NotNan<$t> {
+            fn translate(&self, delta: &Delta<$t>) -> Self {
+                ordered_float::NotNan::new(self.into_inner().translate(delta)).unwrap_or(*self)
+            }
+        }
+
+        impl Snap for ordered_float::NotNan<$t> {
+            fn snap(&self, step: &Self) -> Self {
+                let snapped = self.into_inner().snap(&step.into_inner());
+                ordered_float::NotNan::new(snapped).unwrap_or(*self)
+            }
+        }
+    )*
+}
+
+#[cfg(feature = "ordered-float")]
+__impl_ordered_float!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(3u8.distance(&5), 2);
+        assert_eq!(i8::MIN.distance(&i8::MAX), 255);
+        assert_eq!(1.5f64.distance(&-1.0), 2.5);
+    }
+
+    #[test]
+    fn midpoint() {
+        assert_eq!(Midpoint::midpoint(&3u8, &8), 5);
+        assert_eq!(Midpoint::midpoint(&250u8, &254), 252);
+        assert_eq!(Midpoint::midpoint(&i8::MIN, &i8::MAX), -1);
+        assert_eq!(Midpoint::midpoint(&7i32, &-3), 2);
+        assert_eq!(Midpoint::midpoint(&1.5f64, &-1.0), 0.25);
+        assert_eq!(Midpoint::midpoint(&f64::MAX, &f64::MAX), f64::MAX);
+    }
+
+    #[test]
+    fn translate() {
+        assert_eq!(10u8.translate(&Delta::Forward(5)), 15);
+        assert_eq!(10u8.translate(&Delta::Backward(15)), 0);
+        assert_eq!(250u8.translate(&Delta::Forward(10)), 255);
+        assert_eq!((-100i8).translate(&Delta::Forward(200)), 100);
+        assert_eq!(100i8.translate(&Delta::Backward(255)), -128);
+        assert_eq!(1.5f64.translate(&Delta::Backward(2.0)), -0.5);
+    }
+
+    #[test]
+    fn snap() {
+        assert_eq!(12u8.snap(&5), 10);
+        assert_eq!(13u8.snap(&5), 15);
+        assert_eq!(254u8.snap(&100), 200);
+        assert_eq!(i8::MIN.snap(&100), -100);
+        assert_eq!((-12i32).snap(&5), -10);
+        assert_eq!((-13i32).snap(&5), -15);
+        assert_eq!(7i32.snap(&0), 7);
+        assert_eq!(7i32.snap(&-1), 7);
+        assert_eq!(i8::MIN.snap(&-1), i8::MIN);
+        assert_eq!(i64::MIN.snap(&-1), i64::MIN);
+        assert_eq!((-13i32).snap(&-5), -15);
+        assert_eq!(10.03f64.snap(&0.5), 10.0);
+        assert_eq!(10.3f64.snap(&0.5), 10.5);
+    }
+
+    #[cfg(feature = "ordered-float")]
+    #[test]
+    fn ordered_float_distance() {
+        use ordered_float::{NotNan, OrderedFloat};
+        assert_eq!(OrderedFloat(1.5f64).distance(&OrderedFloat(-1.0)), 2.5);
+        assert_eq!(
+            NotNan::new(1.5f32)
+                .unwrap()
+                .distance(&NotNan::new(-1.0).unwrap()),
+            2.5
+        );
+        let (lo, hi) = (
+            NotNan::new(f64::NEG_INFINITY).unwrap(),
+            NotNan::new(f64::INFINITY).unwrap(),
+        );
+        assert_eq!(Midpoint::midpoint(&lo, &hi), lo);
+        assert_eq!(Midpoint::midpoint(&NotNan::new(1.0).unwrap(), &hi), hi);
+    }
+}
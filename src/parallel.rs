@@ -0,0 +1,225 @@
+use rayon::{
+    iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer},
+    prelude::*,
+};
+
+use super::bound::StartBound;
+use super::interval::Interval;
+use super::interval_map::{ConflictPolicy, IntervalMap, MergedValue};
+
+pub struct ParIter<'a, K: 'a, V: 'a>(rayon::slice::Iter<'a, (Interval<K>, V)>);
+
+pub struct ParIterMut<'a, K: 'a, V: 'a>(rayon::slice::IterMut<'a, (Interval<K>, V)>);
+
+pub struct ParValuesMut<'a, K: 'a, V: 'a>(rayon::slice::IterMut<'a, (Interval<K>, V)>);
+
+#[doc(hidden)]
+macro __impl_par_iter($name:ident<$a:lifetime, $k:ident, $v:ident>, $item:ty, $f:expr) {
+    impl<$a, $k, $v> ParallelIterator for $name<$a, $k, $v>
+    where
+        $k: $a + Send + Sync,
+        $v: $a + Send + Sync,
+    {
+        type Item = $item;
+        fn drive_unindexed<C>(self, consumer: C) -> C::Result
+        where
+            C: UnindexedConsumer<Self::Item>,
+        {
+            self.0.map($f).drive_unindexed(consumer)
+        }
+        fn opt_len(&self) -> Option<usize> {
+            Some(self.0.len())
+        }
+    }
+
+    impl<$a, $k, $v> IndexedParallelIterator for $name<$a, $k, $v>
+    where
+        $k: $a + Send + Sync,
+        $v: $a + Send + Sync,
+    {
+        fn drive<C>(self, consumer: C) -> C::Result
+        where
+            C: Consumer<Self::Item>,
+        {
+            self.0.map($f).drive(consumer)
+        }
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+        fn with_producer<CB>(self, callback: CB) -> CB::Output
+        where
+            CB: ProducerCallback<Self::Item>,
+        {
+            self.0.map($f).with_producer(callback)
+        }
+    }
+}
+
+__impl_par_iter! { ParIter<'a, K, V>, (&'a Interval<K>, &'a V), |(i, v)| (i, v) }
+__impl_par_iter! { ParIterMut<'a, K, V>, (&'a Interval<K>, &'a mut V), |(i, v)| (&*i, v) }
+__impl_par_iter! { ParValuesMut<'a, K, V>, &'a mut V, |(_, v)| v }
+
+fn cut<K, V>(map: &IntervalMap<K, V>, points: &[StartBound<K>]) -> Vec<IntervalMap<K, V>>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    let mut chunks = vec![vec![]];
+    let mut n = 0;
+    for (i, v) in map.iter() {
+        let mut i = i.clone();
+        while n < points.len() && points[n] <= i.end {
+            if i.start < points[n] {
+                chunks[n].push((
+                    Interval {
+                        start: i.start,
+                        end: points[n].clone().into(),
+                    },
+                    v.clone(),
+                ));
+                i.start = points[n].clone();
+            }
+            chunks.push(vec![]);
+            n += 1;
+        }
+        chunks[n].push((i, v.clone()));
+    }
+    chunks.resize(points.len() + 1, vec![]);
+    chunks
+        .into_iter()
+        .map(|inner| unsafe { IntervalMap::from_inner_unchecked(inner) })
+        .collect()
+}
+
+impl<K, V> IntervalMap<K, V>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+{
+    pub fn par_iter(&self) -> ParIter<K, V> {
+        ParIter(self.inner().par_iter())
+    }
+    pub fn par_iter_mut(&mut self) -> ParIterMut<K, V> {
+        ParIterMut(self.inner_mut().par_iter_mut())
+    }
+    pub fn par_values_mut(&mut self) -> ParValuesMut<K, V> {
+        ParValuesMut(self.inner_mut().par_iter_mut())
+    }
+    pub fn par_merge<V2>(&self, other: &IntervalMap<K, V2>) -> IntervalMap<K, MergedValue<V, V2>>
+    where
+        K: Ord + Clone,
+        V: Clone,
+        V2: Clone + Send + Sync,
+    {
+        let step = self.len() / rayon::current_num_threads() + 1;
+        if self.len() <= step {
+            return self.merge(other);
+        }
+        let points: Vec<_> = self
+            .inner()
+            .iter()
+            .skip(step)
+            .step_by(step)
+            .map(|(i, _)| i.start.clone())
+            .collect();
+        let inner: Vec<_> = cut(self, &points)
+            .into_par_iter()
+            .zip(cut(other, &points))
+            .map(|(x, y)| x.merge(&y).into_inner())
+            .collect();
+        unsafe { IntervalMap::from_inner_unchecked(inner.concat()) }
+    }
+}
+
+fn first_wins<K, V>(items: Vec<(Interval<K>, V)>) -> IntervalMap<K, V>
+where
+    K: Ord + Clone + Send,
+    V: Clone + Send,
+{
+    let items: Vec<_> = items
+        .into_par_iter()
+        .filter(|(i, _)| !i.is_empty())
+        .collect();
+    let mut cuts = IntervalMap::cuts(&items);
+    cuts.par_sort();
+    IntervalMap::resolve(items, cuts, ConflictPolicy::FirstWins).0
+}
+
+impl<K, I, V> FromParallelIterator<(I, V)> for IntervalMap<K, V>
+where
+    K: Ord + Clone + Send,
+    I: Into<Interval<K>> + Send,
+    V: Clone + Send,
+{
+    fn from_par_iter<P>(par_iter: P) -> Self
+    where
+        P: IntoParallelIterator<Item = (I, V)>,
+    {
+        first_wins(
+            par_iter
+                .into_par_iter()
+                .map(|(i, v)| (i.into(), v))
+                .collect(),
+        )
+    }
+}
+
+impl<K, V> ParallelExtend<(Interval<K>, V)> for IntervalMap<K, V>
+where
+    K: Ord + Clone + Send,
+    V: Clone + Send,
+{
+    fn par_extend<P>(&mut self, par_iter: P)
+    where
+        P: IntoParallelIterator<Item = (Interval<K>, V)>,
+    {
+        let mut items = std::mem::take(self).into_inner();
+        items.par_extend(par_iter);
+        *self = first_wins(items);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::FromIterator;
+
+    fn sample(n: i32, m: i32) -> Vec<(std::ops::Range<i32>, i32)> {
+        (0..n)
+            .map(|i| {
+                let s = (i * 7919) % m;
+                (s..s + i % 17 + 1, i)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn par_iter() {
+        let mut map: IntervalMap<_, _> = sample(1000, 5000).into_par_iter().collect();
+        assert_eq!(map, IntervalMap::from_iter(sample(1000, 5000)));
+        assert!(map.par_iter().collect::<Vec<_>>() == map.iter().collect::<Vec<_>>());
+        map.par_values_mut().for_each(|v| *v *= 2);
+        map.par_iter_mut().for_each(|(_, v)| *v += 1);
+        assert!(map.values().all(|v| v % 2 == 1));
+        let mut extended = IntervalMap::from_iter(sample(500, 5000));
+        extended.par_extend(
+            sample(1000, 5000)
+                .into_par_iter()
+                .skip(500)
+                .map(|(i, v)| (i.into(), v)),
+        );
+        assert_eq!(extended, IntervalMap::from_iter(sample(1000, 5000)));
+    }
+
+    #[test]
+    fn par_merge() {
+        let map1 = IntervalMap::from_iter(sample(1000, 5000));
+        let map2 = IntervalMap::from_iter(sample(700, 3000));
+        assert_eq!(map1.par_merge(&map2), map1.merge(&map2));
+        assert_eq!(map2.par_merge(&map1), map2.merge(&map1));
+        assert_eq!(
+            map1.par_merge(&IntervalMap::<i32, i32>::new()),
+            map1.merge(&IntervalMap::<i32, i32>::new())
+        );
+    }
+}
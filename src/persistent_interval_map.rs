@@ -0,0 +1,412 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    iter::FromIterator,
+    sync::{atomic, Arc},
+};
+
+use super::interval::Interval;
+use super::interval_map::IntervalMap;
+
+type Link<K, V> = Option<Arc<Node<K, V>>>;
+
+struct Node<K, V> {
+    interval: Interval<K>,
+    value: V,
+    priority: u64,
+    len: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+fn priority() -> u64 {
+    static SEED: atomic::AtomicU64 = atomic::AtomicU64::new(0);
+    let mut x = SEED.fetch_add(0x9e37_79b9_7f4a_7c15, atomic::Ordering::Relaxed);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn len<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |n| n.len)
+}
+
+fn leaf<K, V>(interval: Interval<K>, value: V) -> Link<K, V> {
+    Some(Arc::new(Node {
+        interval,
+        value,
+        priority: priority(),
+        len: 1,
+        left: None,
+        right: None,
+    }))
+}
+
+fn with_children<K, V>(node: &Node<K, V>, left: Link<K, V>, right: Link<K, V>) -> Link<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    Some(Arc::new(Node {
+        interval: node.interval.clone(),
+        value: node.value.clone(),
+        priority: node.priority,
+        len: len(&left) + len(&right) + 1,
+        left,
+        right,
+    }))
+}
+
+fn split<K, V, F>(link: &Link<K, V>, pred: &F) -> (Link<K, V>, Link<K, V>)
+where
+    K: Clone,
+    V: Clone,
+    F: Fn(&Interval<K>) -> bool,
+{
+    match link {
+        None => (None, None),
+        Some(n) if pred(&n.interval) => {
+            let (l, r) = split(&n.right, pred);
+            (with_children(n, n.left.clone(), l), r)
+        }
+        Some(n) => {
+            let (l, r) = split(&n.left, pred);
+            (l, with_children(n, r, n.right.clone()))
+        }
+    }
+}
+
+fn merge<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    match (left, right) {
+        (None, r) => r,
+        (l, None) => l,
+        (Some(l), Some(r)) => {
+            if l.priority > r.priority {
+                let right = merge(l.right.clone(), Some(r));
+                with_children(&l, l.left.clone(), right)
+            } else {
+                let left = merge(Some(l), r.left.clone());
+                with_children(&r, left, r.right.clone())
+            }
+        }
+    }
+}
+
+fn rank<K, V, F>(mut link: &Link<K, V>, pred: F) -> usize
+where
+    F: Fn(&Interval<K>) -> bool,
+{
+    let mut n = 0;
+    while let Some(node) = link {
+        if pred(&node.interval) {
+            n += len(&node.left) + 1;
+            link = &node.right;
+        } else {
+            link = &node.left;
+        }
+    }
+    n
+}
+
+fn edge<K, V>(mut link: &Link<K, V>, left: bool) -> Option<&Node<K, V>> {
+    let mut edge = None;
+    while let Some(node) = link {
+        edge = Some(&**node);
+        link = if left { &node.left } else { &node.right };
+    }
+    edge
+}
+
+pub struct PersistentIntervalMap<K, V> {
+    root: Link<K, V>,
+}
+
+impl<K, V> PersistentIntervalMap<K, V> {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+    pub fn len(&self) -> usize {
+        len(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+    pub fn iter(&self) -> Iter<K, V> {
+        Iter::new(&self.root, 0, self.len())
+    }
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&Interval<K>, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            match node.interval.partial_cmp(key).unwrap() {
+                Ordering::Less => link = &node.right,
+                Ordering::Greater => link = &node.left,
+                Ordering::Equal => return Some((&node.interval, &node.value)),
+            }
+        }
+        None
+    }
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.get_key_value(key).is_some()
+    }
+    pub fn range<R>(&self, r: R) -> Iter<K, V>
+    where
+        K: Ord,
+        R: Into<Interval<K>>,
+    {
+        let i = r.into();
+        let start = rank(&self.root, |interval| interval.start < i.start);
+        let end = rank(&self.root, |interval| interval.end <= i.end);
+        Iter::new(&self.root, start, end.saturating_sub(start))
+    }
+}
+
+impl<K, V> PersistentIntervalMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn carve(&self, interval: &Interval<K>) -> [Link<K, V>; 3] {
+        let (left, rest) = split(&self.root, &|i: &Interval<K>| i.end < interval.start);
+        let (middle, right) = split(&rest, &|i: &Interval<K>| i.start <= interval.end);
+        [left, middle, right]
+    }
+    fn remnants(middle: &Link<K, V>, interval: &Interval<K>) -> [Link<K, V>; 2] {
+        [
+            edge(middle, true).and_then(|n| match n.interval.clone().remove(interval) {
+                [Some(i), _] => leaf(i, n.value.clone()),
+                _ => None,
+            }),
+            edge(middle, false).and_then(|n| match n.interval.clone().remove(interval) {
+                [_, Some(i)] => leaf(i, n.value.clone()),
+                _ => None,
+            }),
+        ]
+    }
+    pub fn insert<T: Into<Interval<K>>>(&self, key: T, val: V) -> Self {
+        let interval = key.into();
+        if interval.is_empty() {
+            return self.clone();
+        }
+        let [left, middle, right] = self.carve(&interval);
+        let mut entries = Iter::new(&middle, 0, len(&middle)).peekable();
+        let mut filled = None;
+        for gap in interval.gaps(Iter::new(&middle, 0, len(&middle)).map(|(i, _)| i)) {
+            while let Some((i, v)) = entries.next_if(|(i, _)| i.end < gap.start) {
+                filled = merge(filled, leaf(i.clone(), v.clone()));
+            }
+            filled = merge(filled, leaf(gap, val.clone()));
+        }
+        for (i, v) in entries {
+            filled = merge(filled, leaf(i.clone(), v.clone()));
+        }
+        Self {
+            root: merge(merge(left, filled), right),
+        }
+    }
+    pub fn overwrite<T: Into<Interval<K>>>(&self, key: T, val: V) -> Self {
+        let interval = key.into();
+        if interval.is_empty() {
+            return self.clone();
+        }
+        let [left, middle, right] = self.carve(&interval);
+        let [before, after] = Self::remnants(&middle, &interval);
+        let middle = merge(merge(before, leaf(interval, val)), after);
+        Self {
+            root: merge(merge(left, middle), right),
+        }
+    }
+    pub fn remove<T: Into<Interval<K>>>(&self, key: T) -> Self {
+        let interval = key.into();
+        if interval.is_empty() {
+            return self.clone();
+        }
+        let [left, middle, right] = self.carve(&interval);
+        let [before, after] = Self::remnants(&middle, &interval);
+        Self {
+            root: merge(merge(left, merge(before, after)), right),
+        }
+    }
+}
+
+impl<K, V> Clone for PersistentIntervalMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<K, V> Default for PersistentIntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> PartialEq for PersistentIntervalMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for PersistentIntervalMap<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for PersistentIntervalMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> From<IntervalMap<K, V>> for PersistentIntervalMap<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn from(map: IntervalMap<K, V>) -> Self {
+        Self {
+            root: map
+                .into_iter()
+                .fold(None, |root, (i, v)| merge(root, leaf(i, v))),
+        }
+    }
+}
+
+impl<'a, K, V> From<&'a PersistentIntervalMap<K, V>> for IntervalMap<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn from(map: &'a PersistentIntervalMap<K, V>) -> Self {
+        let inner = map.iter().map(|(i, v)| (i.clone(), v.clone())).collect();
+        unsafe { IntervalMap::from_inner_unchecked(inner) }
+    }
+}
+
+impl<K, I, V> FromIterator<(I, V)> for PersistentIntervalMap<K, V>
+where
+    K: Ord + Clone,
+    I: Into<Interval<K>>,
+    V: Clone,
+{
+    fn from_iter<T: IntoIterator<Item = (I, V)>>(iter: T) -> Self {
+        Self::from(iter.into_iter().collect::<IntervalMap<K, V>>())
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a PersistentIntervalMap<K, V> {
+    type Item = (&'a Interval<K>, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, K: 'a, V: 'a> {
+    stack: Vec<&'a Node<K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K: 'a, V: 'a> Iter<'a, K, V> {
+    fn new(mut link: &'a Link<K, V>, mut index: usize, remaining: usize) -> Self {
+        let mut stack = vec![];
+        while let Some(node) = link {
+            let l = len(&node.left);
+            if index < l {
+                stack.push(&**node);
+                link = &node.left;
+            } else if index == l {
+                stack.push(&**node);
+                break;
+            } else {
+                index -= l + 1;
+                link = &node.right;
+            }
+        }
+        Self { stack, remaining }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
+    type Item = (&'a Interval<K>, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.stack.pop()?;
+        self.remaining -= 1;
+        let mut link = &node.right;
+        while let Some(n) = link {
+            self.stack.push(n);
+            link = &n.left;
+        }
+        Some((&node.interval, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persistent_interval_map() {
+        let mut map = IntervalMap::new();
+        let mut persistent = PersistentIntervalMap::new();
+        let mut versions = vec![];
+        for (n, i) in (0..40)
+            .map(|n| (n, (n * 37) % 101))
+            .map(|(n, i)| (n, i..i + n % 13 + 1))
+        {
+            match n % 3 {
+                0 => {
+                    map.insert(i.clone(), n);
+                    persistent = persistent.insert(i, n);
+                }
+                1 => {
+                    map.overwrite(i.clone(), n);
+                    persistent = persistent.overwrite(i, n);
+                }
+                _ => {
+                    map.remove(i.clone());
+                    persistent = persistent.remove(i);
+                }
+            }
+            assert_eq!(IntervalMap::from(&persistent), map);
+            versions.push((map.clone(), persistent.clone()));
+        }
+        for (map, persistent) in &versions {
+            assert_eq!(&IntervalMap::from(persistent), map);
+            assert_eq!(persistent.len(), map.len());
+            for k in -1..120 {
+                assert_eq!(persistent.get(&k), map.get(&k));
+            }
+            assert!(persistent.range(20..60).eq(map.range(20..60)));
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{collections::Bound, iter::FromIterator};
+
+use interval_map::{
+    bound::StartBound,
+    interval_map::{MergedValue, SliverPolicy},
+    DepthMap, Interval, IntervalMap, Measure,
+};
+use proptest::prelude::*;
+
+const POINTS: std::ops::RangeInclusive<i32> = -4..=24;
+
+type Model<V> = Vec<Option<V>>;
+
+fn key() -> impl Strategy<Value = i32> {
+    (0..10).prop_map(|k| k * 2)
+}
+
+fn bound() -> impl Strategy<Value = Bound<i32>> {
+    prop_oneof![
+        Just(Bound::Unbounded),
+        key().prop_map(Bound::Included),
+        key().prop_map(Bound::Excluded),
+    ]
+}
+
+fn interval() -> impl Strategy<Value = Interval<i32>> {
+    (bound(), bound()).prop_map(|(start, end)| Interval::new(start, end))
+}
+
+fn map() -> impl Strategy<Value = IntervalMap<i32, u8>> {
+    prop::collection::vec((interval(), 0..4u8), 0..8).prop_map(IntervalMap::from_iter)
+}
+
+fn model<V: Clone>(map: &IntervalMap<i32, V>) -> Model<V> {
+    POINTS.map(|p| map.get(&p).cloned()).collect()
+}
+
+fn contains(interval: &Interval<i32>, p: i32) -> bool {
+    interval.start <= p && interval.end >= p
+}
+
+fn touches(prev: &Interval<i32>, next: &Interval<i32>) -> bool {
+    match (prev.end.0, next.start.0) {
+        (Bound::Included(x), Bound::Excluded(y)) | (Bound::Excluded(x), Bound::Included(y)) => {
+            x == y
+        }
+        _ => false,
+    }
+}
+
+fn check<V>(map: &IntervalMap<i32, V>) {
+    assert!(map.keys().all(|i| !i.is_empty()));
+    assert!(map.inner().windows(2).all(|w| w[0].0.end < w[1].0.start));
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(Interval<i32>, u8),
+    InsertWith(Interval<i32>, u8),
+    Overwrite(Interval<i32>, u8),
+    Remove(Interval<i32>),
+    Append(IntervalMap<i32, u8>),
+    Invert(u8),
+    SplitOff(i32),
+    SplitOffAt(Bound<i32>),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (interval(), 0..4u8).prop_map(|(i, v)| Op::Insert(i, v)),
+        (interval(), 0..4u8).prop_map(|(i, v)| Op::InsertWith(i, v)),
+        (interval(), 0..4u8).prop_map(|(i, v)| Op::Overwrite(i, v)),
+        interval().prop_map(Op::Remove),
+        map().prop_map(Op::Append),
+        (0..4u8).prop_map(Op::Invert),
+        key().prop_map(Op::SplitOff),
+        bound().prop_map(Op::SplitOffAt),
+    ]
+}
+
+fn apply(map: &mut IntervalMap<i32, u8>, model: &mut Model<u8>, op: Op) {
+    match op {
+        Op::Insert(i, v) => {
+            for (p, m) in POINTS.zip(model.iter_mut()) {
+                if contains(&i, p) && m.is_none() {
+                    *m = Some(v);
+                }
+            }
+            map.insert(i, v);
+        }
+        Op::InsertWith(i, v) => {
+            for (p, m) in POINTS.zip(model.iter_mut()) {
+                if contains(&i, p) {
+                    *m = Some(m.map_or(v, |x| x.wrapping_add(v)));
+                }
+            }
+            map.insert_with(i, v, |x, y| *x = x.wrapping_add(*y));
+        }
+        Op::Overwrite(i, v) => {
+            for (p, m) in POINTS.zip(model.iter_mut()) {
+                if contains(&i, p) {
+                    *m = Some(v);
+                }
+            }
+            map.overwrite(i, v);
+        }
+        Op::Remove(i) => {
+            for (p, m) in POINTS.zip(model.iter_mut()) {
+                if contains(&i, p) {
+                    *m = None;
+                }
+            }
+            map.remove(i);
+        }
+        Op::Append(mut other) => {
+            for (m, o) in model.iter_mut().zip(self::model(&other)) {
+                if m.is_none() {
+                    *m = o;
+                }
+            }
+            map.append(&mut other);
+            assert!(other.is_empty());
+        }
+        Op::Invert(v) => {
+            for m in model.iter_mut() {
+                *m = match m {
+                    Some(_) => None,
+                    None => Some(v),
+                };
+            }
+            map.invert(v);
+        }
+        Op::SplitOff(k) => {
+            let right = map.split_off(k);
+            split(model, right, |p| p >= k);
+        }
+        Op::SplitOffAt(at) => {
+            let at = StartBound(at);
+            let right = map.split_off_at(at);
+            split(model, right, |p| at <= p);
+        }
+    }
+}
+
+fn split<F: Fn(i32) -> bool>(model: &mut Model<u8>, right: IntervalMap<i32, u8>, is_right: F) {
+    check(&right);
+    let mut expected = model.clone();
+    for (p, (m, e)) in POINTS.zip(model.iter_mut().zip(expected.iter_mut())) {
+        if is_right(p) {
+            *m = None;
+        } else {
+            *e = None;
+        }
+    }
+    assert_eq!(self::model(&right), expected);
+}
+
+proptest! {
+    #[test]
+    fn operations(ops in prop::collection::vec(op(), 1..16)) {
+        let mut map = IntervalMap::new();
+        let mut model = model(&map);
+        for op in ops {
+            apply(&mut map, &mut model, op);
+            check(&map);
+            prop_assert_eq!(self::model(&map), model.clone());
+        }
+    }
+
+    #[test]
+    fn merge(map1 in map(), map2 in map()) {
+        let merged = map1.merge(&map2);
+        check(&merged);
+        let expected: Model<_> = model(&map1)
+            .into_iter()
+            .zip(model(&map2))
+            .map(|x| match x {
+                (Some(x), Some(y)) => Some(MergedValue::Both(x, y)),
+                (Some(x), None) => Some(MergedValue::Left(x)),
+                (None, Some(y)) => Some(MergedValue::Right(y)),
+                (None, None) => None,
+            })
+            .collect();
+        prop_assert_eq!(model(&merged), expected);
+    }
+
+    #[test]
+    fn depth(intervals in prop::collection::vec(interval(), 0..8), n in 1..4usize) {
+        let swept = DepthMap::from_iter(intervals.clone());
+        let mut added = DepthMap::new();
+        for i in &intervals {
+            added.add(*i);
+        }
+        check(&swept);
+        prop_assert_eq!(&swept, &added);
+        let threshold: Vec<_> = swept.threshold(n).collect();
+        for p in POINTS {
+            let depth = intervals.iter().filter(|i| contains(i, p)).count();
+            prop_assert_eq!(swept.depth_at(&p), depth);
+            prop_assert_eq!(threshold.iter().any(|i| contains(i, p)), depth >= n);
+        }
+        for i in &intervals {
+            added.subtract(*i);
+        }
+        prop_assert!(added.is_empty());
+    }
+
+    #[test]
+    fn slivers(
+        mut map in map(),
+        min_len in 1..6u32,
+        policy in prop_oneof![
+            Just(SliverPolicy::Previous),
+            Just(SliverPolicy::Next),
+            Just(SliverPolicy::Larger),
+        ],
+    ) {
+        let expected = model(&map).iter().filter(|m| m.is_some()).count();
+        map.absorb_slivers(min_len, policy);
+        check(&map);
+        prop_assert_eq!(model(&map).iter().filter(|m| m.is_some()).count(), expected);
+        let entries = map.inner();
+        for (n, (i, _)) in entries.iter().enumerate() {
+            let short = match (i.start.value(), i.end.value()) {
+                (Some(s), Some(e)) => s.distance(e) < min_len,
+                _ => false,
+            };
+            if !short {
+                continue;
+            }
+            let prev = n > 0 && touches(&entries[n - 1].0, i);
+            let next = n + 1 < entries.len() && touches(i, &entries[n + 1].0);
+            match policy {
+                SliverPolicy::Previous => prop_assert!(!prev),
+                SliverPolicy::Next => prop_assert!(!next),
+                SliverPolicy::Larger => prop_assert!(!prev && !next),
+            }
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary(data in prop::collection::vec(any::<u8>(), 0..256)) {
+        use arbitrary::{Arbitrary, Unstructured};
+        let mut u = Unstructured::new(&data);
+        if let Ok(map) = IntervalMap::<i32, u8>::arbitrary(&mut u) {
+            check(&map);
+        }
+    }
+}